@@ -0,0 +1,98 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::meta::ParseNestedMeta;
+use syn::{Error, Ident, LitInt, LitStr, Type, Visibility};
+
+/// Arguments of `#[fuse_handler(...)]`, all optional.
+#[derive(Default)]
+pub(crate) struct HandlerDoc {
+    summary: Option<LitStr>,
+    description: Option<LitStr>,
+    tags: Vec<LitStr>,
+    request: Option<Type>,
+    response: Option<Type>,
+    status: Option<u16>,
+}
+
+impl HandlerDoc {
+    pub(crate) fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("summary") {
+            self.summary = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("description") {
+            self.description = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("tag") {
+            self.tags.push(meta.value()?.parse()?);
+        } else if meta.path.is_ident("request") {
+            self.request = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("response") {
+            self.response = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("status") {
+            let n: LitInt = meta.value()?.parse()?;
+            let status = n.base10_parse::<u16>()?;
+            if !(100..=599).contains(&status) {
+                return Err(Error::new(n.span(), "status must be between 100 and 599"));
+            }
+            self.status = Some(status);
+        } else {
+            return Err(meta.error("expected `summary`, `description`, `tag`, `request`, `response` or `status`"));
+        }
+        Ok(())
+    }
+
+    /// The doc as a `<HANDLER>_DOC` const next to the handler, attached to an endpoint with `opt().doc(..)`;
+    /// nothing for a handler without arguments.
+    pub(crate) fn expand(&self, vis: &Visibility, handler: &Ident) -> TokenStream {
+        let is_empty = self.summary.is_none()
+            && self.description.is_none()
+            && self.tags.is_empty()
+            && self.request.is_none()
+            && self.response.is_none()
+            && self.status.is_none();
+        if is_empty {
+            return quote! {};
+        }
+
+        let opt_str = |v: &Option<LitStr>| match v {
+            Some(s) => quote! { Some(#s) },
+            None => quote! { None },
+        };
+        let opt_schema = |v: &Option<Type>| match v {
+            Some(t) => quote! { Some(<#t as rmod::fuse::FuseSchema>::schema) },
+            None => quote! { None },
+        };
+        let summary = opt_str(&self.summary);
+        let description = opt_str(&self.description);
+        let tags = &self.tags;
+        let request = opt_schema(&self.request);
+        let response = opt_schema(&self.response);
+        let status = match self.status {
+            Some(s) => quote! { Some(#s) },
+            None => quote! { None },
+        };
+
+        let name = format_ident!("{}_DOC", handler.unraw().to_string().to_uppercase(), span = handler.span());
+        let comment = format!("OpenAPI doc of [`{}`], attach it to its endpoint with `opt().doc({})`.", handler, name);
+        quote! {
+            #[doc = #comment]
+            #vis const #name: rmod::fuse::FuseDoc = rmod::fuse::FuseDoc {
+                summary: #summary,
+                description: #description,
+                tags: &[#(#tags),*],
+                request: #request,
+                response: #response,
+                status: #status,
+            };
+        }
+    }
+}
@@ -12,15 +12,24 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::{ItemFn, parse_macro_input};
 
+/// Turns `async fn name(ctx: &mut FuseRContext) -> FuseResult` into a `FuseHandler`. The optional arguments document
+/// the endpoint in the OpenAPI document served by fuse:
+/// `#[fuse_handler(summary = "Create order", description = "...", tag = "orders", request = CreateOrder, response = Order, status = 201)]`,
+/// where the request and response types implement `FuseSchema`. They become a `NAME_DOC` const, attached to the
+/// endpoint with `fuse.option("POST: /orders", opt().doc(CREATE_ORDER_DOC))`.
 #[proc_macro_attribute]
-pub fn fuse_handler(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn fuse_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as ItemFn);
+    let mut args = handler_doc::HandlerDoc::default();
+    let parser = syn::meta::parser(|meta| args.parse(meta));
+    parse_macro_input!(attr with parser);
 
     let vis = &input.vis;
     let sig = &input.sig;
     let body = &input.block;
     let name = &sig.ident;
     let attrs = &input.attrs;
+    let doc = args.expand(vis, name);
 
     // We expect the signature to be: async fn name(ctx: &mut FuseRContext) -> FuseResult
     // We will transform it to: fn name(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult>
@@ -30,7 +39,33 @@ pub fn fuse_handler(_attr: TokenStream, item: TokenStream) -> TokenStream {
         #vis fn #name(ctx: &mut rmod::fuse::FuseRContext) -> rmod::fuse::BoxFuture<'_, rmod::fuse::FuseResult> {
             Box::pin(async move #body)
         }
+
+        #doc
     };
 
     TokenStream::from(expanded)
 }
+
+/// Derives `rmod::validate::Validate` from `#[validate(...)]` field attributes:
+/// `required`, `length(min = 1, max = 64)`, `range(min = 0, max = "100.5")`, `regex = "^[a-z]+$"`,
+/// `one_of("buy", "sell")`, `precision = 8`, `nested` and `custom = "path::to::fn"`.
+/// Error paths use the JSON names, honouring `#[serde(rename)]` and `#[serde(rename_all)]`.
+#[proc_macro_derive(Validate, attributes(validate))]
+pub fn derive_validate(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as syn::DeriveInput);
+    validate::expand(input).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+/// Derives `rmod::fuse::FuseSchema`, the JSON schema of a type in the OpenAPI document. Structs with named fields
+/// become components, newtype structs their inner type and unit-only enums a string enum; doc comments become
+/// descriptions and `#[serde(rename, rename_all, default, skip)]` are honoured.
+#[proc_macro_derive(FuseSchema)]
+pub fn derive_fuse_schema(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as syn::DeriveInput);
+    schema::expand(input).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+mod handler_doc;
+mod schema;
+mod serde_attr;
+mod validate;
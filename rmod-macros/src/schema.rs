@@ -0,0 +1,128 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use crate::serde_attr::{rename, rename_variant, serde_flag, serde_str};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Expr, ExprLit, Fields, Lit};
+
+pub(crate) fn expand(mut input: DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    let name = ident.to_string();
+    let description = opt_str(doc_comment(&input.attrs));
+    let rename_all = serde_str(&input.attrs, "rename_all")?;
+
+    let schema = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                let container_default = serde_flag(&input.attrs, "default")?;
+                let mut properties = Vec::new();
+                for field in &fields.named {
+                    if serde_flag(&field.attrs, "skip")? {
+                        continue;
+                    }
+                    if serde_flag(&field.attrs, "flatten")? {
+                        return Err(Error::new(
+                            field.ident.as_ref().map_or(ident.span(), |i| i.span()),
+                            "FuseSchema does not support flatten",
+                        ));
+                    }
+                    let field_ident = field.ident.as_ref().expect("named field");
+                    let field_name = match serde_str(&field.attrs, "rename")? {
+                        Some(name) => name,
+                        None => rename(&field_ident.to_string(), rename_all.as_deref(), field_ident.span())?,
+                    };
+                    // absent from the JSON when a default fills it in or when serialization may leave it out
+                    let optional =
+                        container_default || serde_flag(&field.attrs, "default")? || serde_flag(&field.attrs, "skip_serializing_if")?;
+                    let field_description = opt_str(doc_comment(&field.attrs));
+                    let ty = &field.ty;
+                    properties.push(quote! {
+                        o.field::<#ty>(schemas, #field_name, #optional, #field_description);
+                    });
+                }
+                quote! {
+                    let mut o = ::rmod::fuse::FuseSchemaObject::new(#description);
+                    #(#properties)*
+                    o.build()
+                }
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+                quote! { <#ty as ::rmod::fuse::FuseSchema>::schema(schemas) }
+            }
+            _ => return Err(Error::new(ident.span(), "FuseSchema can only be derived for structs with named fields or newtype structs")),
+        },
+        Data::Enum(data) => {
+            let mut values = Vec::new();
+            for variant in &data.variants {
+                if !matches!(variant.fields, Fields::Unit) {
+                    return Err(Error::new(variant.ident.span(), "FuseSchema can only be derived for enums with unit variants"));
+                }
+                if serde_flag(&variant.attrs, "skip")? {
+                    continue;
+                }
+                values.push(match serde_str(&variant.attrs, "rename")? {
+                    Some(name) => name,
+                    None => rename_variant(&variant.ident.to_string(), rename_all.as_deref(), variant.ident.span())?,
+                });
+            }
+            quote! { ::rmod::fuse::FuseSchemaGen::string_enum(#description, &[#(#values),*]) }
+        }
+        Data::Union(_) => return Err(Error::new(ident.span(), "FuseSchema cannot be derived for unions")),
+    };
+
+    // a generic type has a different schema per instantiation, so it is inlined instead of becoming a component
+    let is_generic = input.generics.type_params().next().is_some();
+    let body = if is_generic || matches!(&input.data, Data::Struct(d) if matches!(d.fields, Fields::Unnamed(_))) {
+        quote! { #schema }
+    } else {
+        quote! { schemas.component(#name, |schemas| { #schema }) }
+    };
+
+    let params: Vec<_> = input.generics.type_params().map(|p| p.ident.clone()).collect();
+    let where_clause = input.generics.make_where_clause();
+    for param in params {
+        where_clause.predicates.push(syn::parse_quote! { #param: ::rmod::fuse::FuseSchema });
+    }
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::rmod::fuse::FuseSchema for #ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn schema(schemas: &mut ::rmod::fuse::FuseSchemaGen) -> ::rmod::json::Value {
+                #body
+            }
+        }
+    })
+}
+
+/// The `///` lines of an item, trimmed and joined.
+fn doc_comment(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            syn::Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(ExprLit { lit: Lit::Str(s), .. }) => Some(s.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    let doc = lines.join("\n").trim().to_string();
+    if doc.is_empty() { None } else { Some(doc) }
+}
+
+fn opt_str(v: Option<String>) -> TokenStream {
+    match v {
+        Some(s) => quote! { Some(#s) },
+        None => quote! { None },
+    }
+}
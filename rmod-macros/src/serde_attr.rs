@@ -0,0 +1,90 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use syn::{Error, Expr, LitStr, Token};
+
+/// Value of `#[serde(key = "...")]`, ignoring the other serde options.
+pub(crate) fn serde_str(attrs: &[syn::Attribute], key: &str) -> syn::Result<Option<String>> {
+    let mut found = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident(key) {
+                if let Ok(value) = meta.value() {
+                    let s: LitStr = value.parse()?;
+                    found = Some(s.value());
+                }
+            } else if meta.input.peek(Token![=]) {
+                let _: Expr = meta.value()?.parse()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let _content;
+                syn::parenthesized!(_content in meta.input);
+            }
+            Ok(())
+        })?;
+    }
+    Ok(found)
+}
+
+pub(crate) fn rename(field: &str, rule: Option<&str>, span: proc_macro2::Span) -> syn::Result<String> {
+    let field = field.strip_prefix("r#").unwrap_or(field);
+    let words: Vec<&str> = field.split('_').filter(|w| !w.is_empty()).collect();
+    let capitalize = |w: &str| {
+        let mut c = w.chars();
+        c.next().map(|f| f.to_uppercase().chain(c).collect::<String>()).unwrap_or_default()
+    };
+
+    Ok(match rule {
+        None | Some("snake_case") => field.to_string(),
+        Some("lowercase") => field.to_lowercase(),
+        Some("UPPERCASE") => field.to_uppercase(),
+        Some("SCREAMING_SNAKE_CASE") => field.to_uppercase(),
+        Some("kebab-case") => field.replace('_', "-"),
+        Some("SCREAMING-KEBAB-CASE") => field.replace('_', "-").to_uppercase(),
+        Some("PascalCase") => words.iter().map(|w| capitalize(w)).collect(),
+        Some("camelCase") => words.iter().enumerate().map(|(i, w)| if i == 0 { w.to_string() } else { capitalize(w) }).collect(),
+        Some(other) => return Err(Error::new(span, format!("unsupported serde rename_all rule '{}'", other))),
+    })
+}
+
+/// Whether `#[serde(key)]` or `#[serde(key = ...)]` is present, e.g. `default` or `skip`.
+pub(crate) fn serde_flag(attrs: &[syn::Attribute], key: &str) -> syn::Result<bool> {
+    let mut found = false;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            found |= meta.path.is_ident(key);
+            if meta.input.peek(Token![=]) {
+                let _: Expr = meta.value()?.parse()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let _content;
+                syn::parenthesized!(_content in meta.input);
+            }
+            Ok(())
+        })?;
+    }
+    Ok(found)
+}
+
+/// `rename` for enum variants, whose names are PascalCase instead of snake_case.
+pub(crate) fn rename_variant(variant: &str, rule: Option<&str>, span: proc_macro2::Span) -> syn::Result<String> {
+    let mut snake = String::new();
+    for (i, c) in variant.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            snake.push('_');
+        }
+        snake.extend(c.to_lowercase());
+    }
+
+    Ok(match rule {
+        None | Some("PascalCase") => variant.to_string(),
+        Some("lowercase") => variant.to_lowercase(),
+        Some("UPPERCASE") => variant.to_uppercase(),
+        Some(_) => rename(&snake, rule, span)?,
+    })
+}
@@ -0,0 +1,158 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use crate::serde_attr::{rename, serde_str};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Error, Expr, ExprLit, ExprUnary, Fields, Lit, LitInt, LitStr, Token, UnOp};
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(input.ident.span(), "Validate can only be derived for structs with named fields"));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new(input.ident.span(), "Validate can only be derived for structs with named fields"));
+    };
+
+    let rename_all = serde_str(&input.attrs, "rename_all")?;
+    let mut checks = Vec::new();
+    for field in &fields.named {
+        let ident = field.ident.as_ref().expect("named field");
+        let rules = field_rules(field)?;
+        if rules.is_empty() {
+            continue;
+        }
+
+        let name = match serde_str(&field.attrs, "rename")? {
+            Some(name) => name,
+            None => rename(&ident.to_string(), rename_all.as_deref(), ident.span())?,
+        };
+        checks.push(quote! {
+            v.field(#name, |v| {
+                let val = &self.#ident;
+                #(#rules)*
+            });
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::rmod::validate::Validate for #ident #ty_generics #where_clause {
+            fn check(&self, v: &mut ::rmod::validate::Validator) {
+                #(#checks)*
+            }
+        }
+    })
+}
+
+fn field_rules(field: &syn::Field) -> syn::Result<Vec<TokenStream>> {
+    let mut rules = Vec::new();
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("validate")) {
+        attr.parse_nested_meta(|meta| {
+            let path = &meta.path;
+            if path.is_ident("required") {
+                rules.push(quote! { ::rmod::validate::required(v, val); });
+            } else if path.is_ident("nested") {
+                rules.push(quote! { ::rmod::validate::Validate::check(val, v); });
+            } else if path.is_ident("length") {
+                let (mut min, mut max) = (quote! { None }, quote! { None });
+                meta.parse_nested_meta(|m| {
+                    let n: LitInt = m.value()?.parse()?;
+                    let n = n.base10_parse::<usize>()?;
+                    if m.path.is_ident("min") {
+                        min = quote! { Some(#n) };
+                    } else if m.path.is_ident("max") {
+                        max = quote! { Some(#n) };
+                    } else {
+                        return Err(m.error("expected `min` or `max`"));
+                    }
+                    Ok(())
+                })?;
+                rules.push(quote! { ::rmod::validate::length(v, val, #min, #max); });
+            } else if path.is_ident("range") {
+                let (mut min, mut max) = (quote! { None }, quote! { None });
+                meta.parse_nested_meta(|m| {
+                    let bound = decimal_literal(&m.value()?.parse()?)?;
+                    if m.path.is_ident("min") {
+                        min = quote! { Some(#bound) };
+                    } else if m.path.is_ident("max") {
+                        max = quote! { Some(#bound) };
+                    } else {
+                        return Err(m.error("expected `min` or `max`"));
+                    }
+                    Ok(())
+                })?;
+                rules.push(quote! { ::rmod::validate::range(v, val, #min, #max); });
+            } else if path.is_ident("regex") {
+                let pattern: LitStr = meta.value()?.parse()?;
+                if let Err(e) = regex::Regex::new(&pattern.value()) {
+                    return Err(Error::new(pattern.span(), format!("invalid regex: {}", e)));
+                }
+                rules.push(quote! {
+                    {
+                        static RE: ::std::sync::OnceLock<::rmod::validate::Regex> = ::std::sync::OnceLock::new();
+                        let re = RE.get_or_init(|| ::rmod::validate::Regex::new(#pattern).expect("checked by derive(Validate)"));
+                        ::rmod::validate::regex(v, val, re);
+                    }
+                });
+            } else if path.is_ident("one_of") {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                let options = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                let options = options.iter();
+                rules.push(quote! { ::rmod::validate::one_of(v, val, &[#(#options),*]); });
+            } else if path.is_ident("precision") {
+                let n: LitInt = meta.value()?.parse()?;
+                let n = n.base10_parse::<u32>()?;
+                rules.push(quote! { ::rmod::validate::precision(v, val, #n); });
+            } else if path.is_ident("custom") {
+                let f: LitStr = meta.value()?.parse()?;
+                let f: syn::ExprPath = f.parse()?;
+                rules.push(quote! {
+                    if let Err(message) = #f(val) {
+                        v.error("custom", message);
+                    }
+                });
+            } else {
+                return Err(meta.error("unknown validate rule"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(rules)
+}
+
+/// `range` bounds may be written as numbers (`-1`, `0.5`) or strings (`"100.25"`); they become strings
+/// parsed as `Decimal` at runtime, so they are checked here.
+fn decimal_literal(expr: &Expr) -> syn::Result<String> {
+    let text = match expr {
+        Expr::Lit(ExprLit { lit: Lit::Int(n), .. }) => n.base10_digits().to_string(),
+        Expr::Lit(ExprLit { lit: Lit::Float(n), .. }) => n.base10_digits().to_string(),
+        Expr::Lit(ExprLit { lit: Lit::Str(s), .. }) => s.value(),
+        Expr::Unary(ExprUnary { op: UnOp::Neg(_), expr, .. }) => format!("-{}", decimal_literal(expr)?),
+        _ => return Err(Error::new(expr.span(), "expected a number")),
+    };
+    let is_decimal = {
+        let digits = text.strip_prefix('-').unwrap_or(&text);
+        let mut parts = digits.splitn(2, '.');
+        let int = parts.next().unwrap_or_default();
+        let frac = parts.next();
+        !int.is_empty()
+            && int.chars().all(|c| c.is_ascii_digit())
+            && frac.is_none_or(|f| !f.is_empty() && f.chars().all(|c| c.is_ascii_digit()))
+    };
+    if !is_decimal {
+        return Err(Error::new(expr.span(), format!("'{}' is not a decimal number", text)));
+    }
+    Ok(text)
+}
@@ -72,5 +72,7 @@ pub async fn get_exp<T: Clone + Send + Sync + 'static>(group_name: &str, key: &s
         if let Some(c) = groups.get(group_name) { c.downcast_ref::<Cache<String, (T, Duration)>>().cloned() } else { None }
     };
 
-    if let Some(c) = cache { c.get(key).await.map(|v| v.0) } else { None }
+    let value = if let Some(c) = cache { c.get(key).await.map(|v| v.0) } else { None };
+    crate::metrics::cache_lookup(group_name, value.is_some());
+    value
 }
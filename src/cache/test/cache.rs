@@ -117,3 +117,20 @@ async fn test_cache_exp() {
     let val2_expired = get_exp::<String>(group, "key2").await;
     assert_eq!(val2_expired, None, "key2 should be expired now");
 }
+
+#[tokio::test]
+async fn test_cache_put_if_absent() {
+    let group = "test_group_absent";
+    add_group_ttl::<()>(group, "10m", 10);
+
+    // Only one of the concurrent inserts of the same key wins
+    let inserted = futures_util::future::join_all((0..8).map(|_| put_ttl_if_absent(group, "nonce", ()))).await;
+    assert_eq!(inserted.iter().filter(|i| **i).count(), 1, "Exactly one insert should win");
+
+    assert!(put_ttl_if_absent(group, "other", ()).await, "A new key is inserted");
+    assert!(!put_ttl_if_absent("test_group_not_added", "nonce", ()).await, "An unknown group inserts nothing");
+
+    remove_ttl::<()>(group, "nonce").await;
+    assert!(put_ttl_if_absent(group, "nonce", ()).await, "A removed key can be inserted again");
+    remove_ttl::<()>("test_group_not_added", "nonce").await;
+}
@@ -45,11 +45,39 @@ pub async fn put_ttl<T: Clone + Send + Sync + 'static>(group_name: &str, key: &s
     }
 }
 
+/// Inserts `value` unless `key` is cached; `true` when it was inserted, `false` when the key was already there or
+/// the group does not exist. Concurrent callers with the same key see exactly one `true`.
+pub async fn put_ttl_if_absent<T: Clone + Send + Sync + 'static>(group_name: &str, key: &str, value: T) -> bool {
+    let cache = {
+        let groups = get_groups().read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(c) = groups.get(group_name) { c.downcast_ref::<Cache<String, T>>().cloned() } else { None }
+    };
+
+    match cache {
+        Some(c) => c.entry(key.to_string()).or_insert(value).await.is_fresh(),
+        None => false,
+    }
+}
+
+/// Drops `key` from the group; nothing happens when the group or the key does not exist.
+pub async fn remove_ttl<T: Clone + Send + Sync + 'static>(group_name: &str, key: &str) {
+    let cache = {
+        let groups = get_groups().read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(c) = groups.get(group_name) { c.downcast_ref::<Cache<String, T>>().cloned() } else { None }
+    };
+
+    if let Some(c) = cache {
+        c.invalidate(key).await;
+    }
+}
+
 pub async fn get_ttl<T: Clone + Send + Sync + 'static>(group_name: &str, key: &str) -> Option<T> {
     let cache = {
         let groups = get_groups().read().unwrap_or_else(|poisoned| poisoned.into_inner());
         if let Some(c) = groups.get(group_name) { c.downcast_ref::<Cache<String, T>>().cloned() } else { None }
     };
 
-    if let Some(c) = cache { c.get(key).await } else { None }
+    let value = if let Some(c) = cache { c.get(key).await } else { None };
+    crate::metrics::cache_lookup(group_name, value.is_some());
+    value
 }
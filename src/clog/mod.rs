@@ -102,12 +102,27 @@ pub fn set_partner_uid(partner_uid: impl Into<String>) {
     });
 }
 
+/// State of the central-log channel; `None` when central logging is not enabled.
+pub(crate) fn channel_status() -> Option<Result<(), String>> {
+    let sender = LOG_SENDER.get()?;
+    if sender.is_closed() {
+        return Some(Err("central log worker has stopped".to_string()));
+    }
+    if sender.capacity() == 0 {
+        return Some(Err("central log buffer is full".to_string()));
+    }
+    Some(Ok(()))
+}
+
 /// Push a log entry asynchronously into the background buffer.
 pub fn push_log(entry: LogEntryRequest) {
     if let Some(sender) = LOG_SENDER.get() {
         match sender.try_send(WorkerCommand::Log(Box::new(entry))) {
             Ok(_) => {}
             Err(mpsc::error::TrySendError::Full(dropped)) => {
+                if let WorkerCommand::Log(dropped_entry) = &dropped {
+                    crate::metrics::clog_dropped(&dropped_entry.log_type);
+                }
                 // Drop policy: Drop non-ERROR logs when buffer is full under extreme backpressure
                 if let WorkerCommand::Log(dropped_entry) = dropped
                     && dropped_entry.log_type == "ERROR"
@@ -222,9 +237,7 @@ pub fn log_db_query(
             payload["stacktrace"] = serde_json::Value::String(clean_st);
         }
     }
-    if let Some(entry) = new_log_entry("DB_QUERY", sql, duration_ms, status_code, payload.to_string()) {
-        push_log(entry);
-    }
+    push_db_log("DB_QUERY", sql, duration_ms, status_code, payload);
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -254,9 +267,7 @@ pub fn log_db_tx_query(
             payload["stacktrace"] = serde_json::Value::String(clean_st);
         }
     }
-    if let Some(entry) = new_log_entry("DB_TX_QUERY", sql, duration_ms, status_code, payload.to_string()) {
-        push_log(entry);
-    }
+    push_db_log("DB_TX_QUERY", sql, duration_ms, status_code, payload);
 }
 
 pub fn log_tx_begin(tx_id: &str, key: Option<&str>, duration_ms: i32, status_code: i32, error_msg: Option<&str>, stacktrace: Option<&str>) {
@@ -272,9 +283,7 @@ pub fn log_tx_begin(tx_id: &str, key: Option<&str>, duration_ms: i32, status_cod
             payload["stacktrace"] = serde_json::Value::String(clean_st);
         }
     }
-    if let Some(entry) = new_log_entry("DB_TX_BEGIN", "BEGIN", duration_ms, status_code, payload.to_string()) {
-        push_log(entry);
-    }
+    push_db_log("DB_TX_BEGIN", "BEGIN", duration_ms, status_code, payload);
 }
 
 pub fn log_tx_commit(
@@ -297,9 +306,7 @@ pub fn log_tx_commit(
             payload["stacktrace"] = serde_json::Value::String(clean_st);
         }
     }
-    if let Some(entry) = new_log_entry("DB_TX_COMMIT", "COMMIT", duration_ms, status_code, payload.to_string()) {
-        push_log(entry);
-    }
+    push_db_log("DB_TX_COMMIT", "COMMIT", duration_ms, status_code, payload);
 }
 
 pub fn log_tx_rollback(
@@ -322,9 +329,7 @@ pub fn log_tx_rollback(
             payload["stacktrace"] = serde_json::Value::String(clean_st);
         }
     }
-    if let Some(entry) = new_log_entry("DB_TX_ROLLBACK", "ROLLBACK", duration_ms, status_code, payload.to_string()) {
-        push_log(entry);
-    }
+    push_db_log("DB_TX_ROLLBACK", "ROLLBACK", duration_ms, status_code, payload);
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -352,9 +357,7 @@ pub fn log_db_update(
             payload["stacktrace"] = serde_json::Value::String(clean_st);
         }
     }
-    if let Some(entry) = new_log_entry("DB_UPDATE", sql, duration_ms, status_code, payload.to_string()) {
-        push_log(entry);
-    }
+    push_db_log("DB_UPDATE", sql, duration_ms, status_code, payload);
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -384,9 +387,7 @@ pub fn log_db_tx_update(
             payload["stacktrace"] = serde_json::Value::String(clean_st);
         }
     }
-    if let Some(entry) = new_log_entry("DB_TX_UPDATE", sql, duration_ms, status_code, payload.to_string()) {
-        push_log(entry);
-    }
+    push_db_log("DB_TX_UPDATE", sql, duration_ms, status_code, payload);
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -414,9 +415,7 @@ pub fn log_db_execute(
             payload["stacktrace"] = serde_json::Value::String(clean_st);
         }
     }
-    if let Some(entry) = new_log_entry("DB_EXEC", sql, duration_ms, status_code, payload.to_string()) {
-        push_log(entry);
-    }
+    push_db_log("DB_EXEC", sql, duration_ms, status_code, payload);
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -446,7 +445,13 @@ pub fn log_db_tx_execute(
             payload["stacktrace"] = serde_json::Value::String(clean_st);
         }
     }
-    if let Some(entry) = new_log_entry("DB_TX_EXEC", sql, duration_ms, status_code, payload.to_string()) {
+    push_db_log("DB_TX_EXEC", sql, duration_ms, status_code, payload);
+}
+
+/// Records the query duration metric and pushes the log entry of a database call.
+fn push_db_log(log_type: &str, action_name: &str, duration_ms: i32, status_code: i32, payload: serde_json::Value) {
+    crate::metrics::db_query(log_type, status_code, duration_ms);
+    if let Some(entry) = new_log_entry(log_type, action_name, duration_ms, status_code, payload.to_string()) {
         push_log(entry);
     }
 }
@@ -458,6 +463,7 @@ pub fn log_grpc_call(action_name: &str, duration_ms: i32, status_code: i32, payl
 }
 
 pub fn log_dist_lock_pg_lock(action_name: &str, duration_ms: i32, status_code: i32, payload_json: String) {
+    crate::metrics::dist_lock_wait("pg", status_code, duration_ms);
     if let Some(entry) = new_log_entry("DIST_LOCK_PG_LOCK", action_name, duration_ms, status_code, payload_json) {
         push_log(entry);
     }
@@ -470,6 +476,7 @@ pub fn log_dist_lock_pg_unlock(action_name: &str, duration_ms: i32, status_code:
 }
 
 pub fn log_dist_lock_redis_lock(action_name: &str, duration_ms: i32, status_code: i32, payload_json: String) {
+    crate::metrics::dist_lock_wait("redis", status_code, duration_ms);
     if let Some(entry) = new_log_entry("DIST_LOCK_REDIS_LOCK", action_name, duration_ms, status_code, payload_json) {
         push_log(entry);
     }
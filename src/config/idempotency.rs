@@ -0,0 +1,33 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::model::RedisLockConfig;
+
+/// Keeps the fuse idempotency responses in the `rmod_idempotency` table of the `db_setup` pool `key`,
+/// creating the table when missing.
+pub async fn idempotency_db(key: &str) -> Result<(), String> {
+    if key.is_empty() {
+        return Err("key cannot be empty".to_string());
+    }
+
+    crate::fuse::initialize_idempotency_db(key).await
+}
+
+/// Keeps the fuse idempotency responses in Redis; `ttl` is not used, each endpoint sets its own.
+pub async fn idempotency_redis(config: &RedisLockConfig) -> Result<(), String> {
+    if config.host.is_empty() {
+        return Err("host cannot be empty".to_string());
+    }
+    if config.port == 0 {
+        return Err("port must be greater than 0".to_string());
+    }
+
+    crate::fuse::initialize_idempotency_redis(config).await
+}
@@ -0,0 +1,23 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::model::JwtConfig;
+
+/// Enables the `fuse::jwt_auth` precondition and the `fuse::jwt_interceptor` gRPC interceptor.
+pub fn jwt_auth(config: &JwtConfig) -> Result<(), String> {
+    if config.secrets.is_empty() || config.secrets.iter().any(|s| s.is_empty()) {
+        return Err("secrets cannot be empty".to_string());
+    }
+    if config.leeway.is_some_and(|l| l < 0) {
+        return Err("leeway cannot be negative".to_string());
+    }
+
+    crate::fuse::initialize_jwt(config)
+}
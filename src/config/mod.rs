@@ -10,8 +10,18 @@
 
 mod app_config;
 mod dist_lock;
+mod idempotency;
+mod jwt;
 mod model;
+mod rate_limit;
+mod tls;
+mod webhook;
 
 pub use app_config::*;
 pub use dist_lock::*;
+pub use idempotency::*;
+pub use jwt::*;
 pub use model::*;
+pub use rate_limit::*;
+pub use tls::*;
+pub use webhook::*;
@@ -30,3 +30,51 @@ pub struct RedisLockConfig {
     pub password: Option<String>,
     pub ttl: Option<i64>,
 }
+
+/// TLS of the fuse REST server. `cert`, `key` and `client_ca` are paths to PEM files, or the PEM itself;
+/// files are watched and reloaded when they change.
+pub struct TlsConfig {
+    pub cert: String,
+    pub key: String,
+    /// CA bundle the client certificates must chain to; enables mTLS.
+    pub client_ca: Option<String>,
+    /// With `client_ca`, still accept clients that send no certificate.
+    pub client_cert_optional: bool,
+}
+
+/// Verification of the bearer tokens accepted by `fuse::jwt_auth` and `fuse::jwt_interceptor`.
+pub struct JwtConfig {
+    /// HS256 secrets a token may be signed with; list the new secret next to the old one while rotating.
+    pub secrets: Vec<String>,
+    /// Required `iss` claim.
+    pub issuer: Option<String>,
+    /// Value the `aud` claim, a string or an array, must contain.
+    pub audience: Option<String>,
+    /// Clock skew allowed on `exp`, `nbf` and `iat`, in seconds; defaults to 60.
+    pub leeway: Option<i64>,
+}
+
+/// What a partner signs for `fuse_webhook!`.
+#[derive(Clone, Copy)]
+pub enum WebhookMessage {
+    /// The body followed by the timestamp header value.
+    BodyTimestamp,
+    /// `{timestamp}.{body}`.
+    TimestampDotBody,
+}
+
+/// HMAC-SHA256 signed partner callbacks, verified by the `fuse_webhook!(name)` precondition.
+pub struct WebhookConfig {
+    /// Secrets a callback may be signed with; list the new secret next to the old one while rotating.
+    pub secrets: Vec<String>,
+    /// Header with the hex or base64 signature, optionally `sha256=` prefixed; several may be comma separated.
+    pub signature_header: String,
+    /// Header with the unix time, in seconds, the callback was signed at.
+    pub timestamp_header: String,
+    /// Header with an id unique per delivery, rejected when seen again; the signed timestamp and body are always
+    /// checked for replays.
+    pub nonce_header: Option<String>,
+    pub message: WebhookMessage,
+    /// How far the timestamp may be from now, in seconds; defaults to 300.
+    pub tolerance: Option<i64>,
+}
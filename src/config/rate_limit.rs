@@ -0,0 +1,23 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::model::RedisLockConfig;
+
+/// Keeps the fuse rate-limit buckets in Redis so the limits hold across replicas; `ttl` is not used.
+pub async fn rate_limit_redis(config: &RedisLockConfig) -> Result<(), String> {
+    if config.host.is_empty() {
+        return Err("host cannot be empty".to_string());
+    }
+    if config.port == 0 {
+        return Err("port must be greater than 0".to_string());
+    }
+
+    crate::fuse::initialize_rate_limit_redis(config).await
+}
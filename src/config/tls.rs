@@ -0,0 +1,24 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::model::TlsConfig;
+
+/// Serves the fuse REST server over HTTPS, with HTTP/2 when the client negotiates it. Without this call TLS is
+/// still enabled when `RMOD_TLS_CERT` and `RMOD_TLS_KEY` are set.
+pub fn rest_tls(config: &TlsConfig) -> Result<(), String> {
+    if config.cert.is_empty() {
+        return Err("cert cannot be empty".to_string());
+    }
+    if config.key.is_empty() {
+        return Err("key cannot be empty".to_string());
+    }
+
+    crate::fuse::initialize_tls(config)
+}
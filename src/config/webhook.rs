@@ -0,0 +1,30 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::model::WebhookConfig;
+use axum::http::HeaderName;
+
+/// Registers the signature check of the `fuse_webhook!(name)` precondition.
+pub fn webhook(name: &str, config: &WebhookConfig) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("name cannot be empty".to_string());
+    }
+    if config.secrets.is_empty() || config.secrets.iter().any(|s| s.is_empty()) {
+        return Err("secrets cannot be empty".to_string());
+    }
+    for header in [Some(&config.signature_header), Some(&config.timestamp_header), config.nonce_header.as_ref()].into_iter().flatten() {
+        HeaderName::try_from(header.as_str()).map_err(|_| format!("invalid header name: {:?}", header))?;
+    }
+    if config.tolerance.is_some_and(|t| t <= 0) {
+        return Err("tolerance must be greater than 0".to_string());
+    }
+
+    crate::fuse::initialize_webhook(name, config)
+}
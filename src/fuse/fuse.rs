@@ -13,7 +13,7 @@ pub use axum;
 use axum::{
     body::Body,
     extract::Request,
-    http::StatusCode,
+    http::{HeaderMap, StatusCode, header},
     response::{IntoResponse, Response},
     routing::{MethodFilter, Router, on},
 };
@@ -25,7 +25,18 @@ use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::{Arc, Mutex, OnceLock};
 
+#[cfg(test)]
+#[path = "test/fuse.rs"]
+mod tests;
+
 static MAX_BODY_SIZE: OnceLock<usize> = OnceLock::new();
+static HANDLER_TIMEOUT: OnceLock<std::time::Duration> = OnceLock::new();
+
+/// Time the precondition and handler chain of an endpoint may take, from `RMOD_HANDLER_TIMEOUT`
+/// (e.g. `30s`); unset or zero means no limit.
+fn handler_timeout() -> std::time::Duration {
+    *HANDLER_TIMEOUT.get_or_init(|| crate::time::to_duration(&crate::util::env::string_or("RMOD_HANDLER_TIMEOUT", "")))
+}
 
 pub type FuseResult = Result<(StatusCode, Arc<dyn Any + Send + Sync>), (StatusCode, Arc<dyn Any + Send + Sync>)>;
 pub type FuseHandler = for<'a> fn(&'a mut FuseRContext) -> BoxFuture<'a, FuseResult>;
@@ -53,6 +64,18 @@ macro_rules! fuse_endpoints {
     };
 }
 
+/// Precondition verifying the partner callbacks registered with `config::webhook(name, ..)`; `name` is a
+/// literal or const.
+#[macro_export]
+macro_rules! fuse_webhook {
+    ($name:expr) => {{
+        fn verify_webhook(ctx: &mut $crate::fuse::FuseRContext) -> $crate::fuse::BoxFuture<'_, $crate::fuse::FuseResult> {
+            Box::pin($crate::fuse::verify_webhook(ctx, $name))
+        }
+        verify_webhook as $crate::fuse::FuseHandler
+    }};
+}
+
 #[macro_export]
 macro_rules! endpoints_inner {
     ($map:ident $(,)?) => {};
@@ -72,7 +95,54 @@ macro_rules! endpoints_inner {
     };
 }
 
+mod fuse_error;
+mod fuse_group;
+mod fuse_health;
+mod fuse_idempotency;
+mod fuse_jwt;
+mod fuse_layer;
+mod fuse_mux;
+mod fuse_openapi;
+mod fuse_option;
+mod fuse_panic;
+mod fuse_rate_limit;
+mod fuse_schema;
+mod fuse_static;
+mod fuse_tls;
+mod fuse_webhook;
+mod fuse_ws;
+mod r_context_body;
 mod r_context_client_ip;
+mod r_context_form;
+mod r_context_header;
+mod r_context_params;
+mod r_context_render;
+mod r_context_sse;
+mod r_context_stream;
+mod r_context_validate;
+
+pub use axum_extra::extract::cookie::{Cookie, SameSite};
+pub use fuse_error::*;
+pub use fuse_group::*;
+pub use fuse_idempotency::{FuseIdempotency, idempotency};
+pub(crate) use fuse_idempotency::{initialize_idempotency_db, initialize_idempotency_redis};
+pub(crate) use fuse_jwt::initialize_jwt;
+pub use fuse_jwt::{jwt_auth, jwt_interceptor};
+pub use fuse_layer::*;
+pub use fuse_mux::rest_grpc;
+pub use fuse_openapi::FuseDoc;
+pub use fuse_option::*;
+pub use fuse_rate_limit::{FuseRateLimit, add_grpc_rate_limit, rate_limit};
+pub(crate) use fuse_rate_limit::{grpc_limiter, initialize_rate_limit_redis, retry_after_secs, validate_grpc_limits};
+pub use fuse_schema::*;
+pub(crate) use fuse_tls::initialize_tls;
+pub(crate) use fuse_webhook::initialize_webhook;
+pub use fuse_webhook::verify_webhook;
+pub use fuse_ws::*;
+pub use r_context_body::add_body_converter;
+pub use r_context_form::*;
+pub use r_context_sse::SseEvent;
+use r_context_stream::{BodyTap, TapState};
 
 #[derive(Clone, Copy, Debug)]
 pub struct FuseResSource {
@@ -89,6 +159,12 @@ impl FuseResSource {
 
 pub struct Fuse {
     router: Router,
+    routes: HashMap<String, &'static str>,
+    shapes: HashMap<String, String>,
+    options: HashMap<&'static str, FuseOptions>,
+    layers: FuseLayers,
+    api_docs: fuse_openapi::FuseApiDocs,
+    errors: Vec<String>,
 }
 
 pub struct FuseRContext {
@@ -99,6 +175,17 @@ pub struct FuseRContext {
     pub res_backtrace: Option<Arc<Backtrace>>,
     pub res_location: Option<&'static std::panic::Location<'static>>,
     pub res_source: FuseResSource,
+    pub res_headers: HeaderMap,
+    pub(crate) ws: Option<(&'static str, FuseWsHandler)>,
+    pub(crate) timeout: Option<std::time::Duration>,
+    pub(crate) idempotency: Option<FuseIdempotency>,
+    /// Set when the request is the first of its `Idempotency-Key`; its response is stored after defer.
+    pub(crate) idempotency_claim: Option<Box<fuse_idempotency::Claim>>,
+    /// Set by `verify_webhook`; its replay keys are released when the final response is not a success.
+    pub(crate) webhook_claim: Option<fuse_webhook::WebhookClaim>,
+    pub(crate) timed_out: Option<(std::time::Duration, FuseResSource)>,
+    pub(crate) panicked: Option<(fuse_panic::FusePanic, FuseResSource)>,
+    running: FuseResSource,
 
     pub response: Option<Response>,
     pub body: Option<axum::body::Bytes>,
@@ -122,30 +209,81 @@ where
 
 impl Fuse {
     pub(crate) fn new() -> Self {
-        Self { router: Router::new() }
+        Self {
+            router: Router::new(),
+            routes: HashMap::new(),
+            shapes: HashMap::new(),
+            options: HashMap::new(),
+            layers: FuseLayers::default(),
+            api_docs: fuse_openapi::FuseApiDocs::default(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Sets the options of an endpoint key; must be called before the key is passed to `endpoints`.
+    pub fn option(&mut self, endpoint_key: &'static str, opt: FuseOptions) {
+        self.options.insert(endpoint_key, opt);
+    }
+
+    /// Title and version of the OpenAPI document served at `/openapi.json`; the title defaults to the clog service name.
+    pub fn api_info(&mut self, title: impl Into<String>, version: impl Into<String>) {
+        self.api_docs.title = Some(title.into());
+        self.api_docs.version = Some(version.into());
     }
 
     pub fn endpoints(&mut self, defer: FuseHandler, precondition: Vec<FuseHandler>, mapping: HashMap<&'static str, Vec<FuseHandler>>) {
+        self.register("", defer, precondition, mapping);
+    }
+
+    pub(crate) fn register(
+        &mut self,
+        prefix: &str,
+        defer: FuseHandler,
+        precondition: Vec<FuseHandler>,
+        mapping: HashMap<&'static str, Vec<FuseHandler>>,
+    ) {
+        let mut mapping: Vec<(&'static str, Vec<FuseHandler>)> = mapping.into_iter().collect();
+        mapping.sort_by_key(|(key, _)| *key);
+
         for (key, handlers) in mapping {
-            let parts: Vec<&str> = key.split(": ").collect();
-            if parts.len() != 2 {
+            let key = fuse_group::prefixed_key(prefix, key);
+            let (methods, path) = match parse_endpoint_key(key) {
+                Ok(v) => v,
+                Err(e) => {
+                    self.errors.push(e);
+                    continue;
+                }
+            };
+
+            if let Err(e) = self.claim_route(key, &methods, &path) {
+                self.errors.push(e);
                 continue;
             }
 
-            let method_str = parts[0];
-            let path = parts[1].to_string();
-
-            let filter = match method_str {
-                "GET" => MethodFilter::GET,
-                "POS" | "POST" => MethodFilter::POST,
-                "PUT" => MethodFilter::PUT,
-                "DEL" | "DELETE" => MethodFilter::DELETE,
-                "PAT" | "PATCH" => MethodFilter::PATCH,
-                _ => MethodFilter::GET,
-            };
+            let filter = methods.iter().skip(1).fold(methods[0].1, |acc, (_, f)| acc.or(*f));
 
             let endpoint_key = key;
             let handlers = Arc::new(handlers);
+            let opt = self.options.get(key).cloned().unwrap_or_default();
+            self.api_docs.add(&methods, &path, opt.doc.unwrap_or_default());
+            let limiter = match &opt.rate_limit {
+                Some(limit) => match limit.validate() {
+                    Ok(_) => Some(Arc::new(fuse_rate_limit::RateLimiter::new(endpoint_key, limit.clone()))),
+                    Err(e) => {
+                        self.errors.push(format!("endpoint '{}': {}", key, e));
+                        continue;
+                    }
+                },
+                None => None,
+            };
+            if let Some(idempotency) = &opt.idempotency {
+                let res =
+                    if opt.stream { Err("idempotency cannot be used with a streamed body".to_string()) } else { idempotency.validate() };
+                if let Err(e) = res {
+                    self.errors.push(format!("endpoint '{}': {}", key, e));
+                    continue;
+                }
+            }
 
             let precondition = Arc::new(precondition.clone());
 
@@ -156,7 +294,11 @@ impl Fuse {
             let path_for_closure = path.clone();
             let handler_fn = move |req: Request<Body>| async move {
                 let (parts, body) = req.into_parts();
-                let bytes = axum::body::to_bytes(body, limit).await.unwrap_or_default();
+                let (bytes, mut body) = if opt.stream {
+                    (axum::body::Bytes::new(), body)
+                } else {
+                    (axum::body::to_bytes(body, limit).await.unwrap_or_default(), Body::empty())
+                };
 
                 let trace_id =
                     parts.headers.get("x-trace-id").and_then(|v| v.to_str().ok()).map(|s| s.to_string()).unwrap_or_else(crate::uid::new);
@@ -173,6 +315,7 @@ impl Fuse {
                     .map(|c| c.exclusion_routes.iter().any(|r| path_clone.starts_with(r) || endpoint_key.contains(r)))
                     .unwrap_or(false);
 
+                let method = parts.method.to_string();
                 let query_params = parts.uri.query().unwrap_or_default().to_string();
                 let user_agent = parts.headers.get("user-agent").and_then(|v| v.to_str().ok()).unwrap_or_default().to_string();
                 let client_ip = parts
@@ -196,15 +339,22 @@ impl Fuse {
                     env_name: env_name.clone(),
                 };
 
-                if !is_excluded && clog_config.is_some() {
-                    let req_body_str = String::from_utf8_lossy(&bytes);
-                    let req_body_val = clog::parse_body_to_json_val(&req_body_str);
+                let is_logged = !is_excluded && clog_config.is_some();
+                if is_logged {
+                    let content_type = parts.headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()).unwrap_or_default();
+                    let req_body_val = if opt.stream {
+                        r_context_stream::stream_request_meta(&parts.headers)
+                    } else if let Some(v) = r_context_form::form_log_val(content_type, &bytes).await {
+                        v
+                    } else {
+                        clog::parse_body_to_json_val(&String::from_utf8_lossy(&bytes))
+                    };
                     let hostname = whoami::fallible::hostname().unwrap_or_else(|_| "unknown".to_string());
 
                     let payload_map = serde_json::json!({
                         "endpoint": endpoint_key,
                         "path": path_clone,
-                        "method": method_str,
+                        "method": method,
                         "query_params": query_params,
                         "client_ip": client_ip,
                         "user_agent": user_agent,
@@ -242,75 +392,152 @@ impl Fuse {
                 }
 
                 let start_time = std::time::Instant::now();
-                let mut ctx = FuseRContext::new(Request::from_parts(parts, Body::from(bytes.clone())));
-                ctx.body = Some(bytes.clone());
+                let req_tap = Arc::new(Mutex::new(TapState::default()));
+                let mut ctx = if opt.stream {
+                    if is_logged {
+                        body = Body::from_stream(BodyTap::new(body, req_tap.clone(), None));
+                    }
+                    FuseRContext::new(Request::from_parts(parts, body))
+                } else {
+                    let mut ctx = FuseRContext::new(Request::from_parts(parts, Body::from(bytes.clone())));
+                    ctx.body = Some(bytes.clone());
+                    ctx
+                };
+
+                ctx.ws = opt.ws.map(|h| (endpoint_key, h));
+                ctx.timeout = Some(opt.timeout.unwrap_or_else(handler_timeout));
+                ctx.idempotency = opt.idempotency.clone();
 
                 crate::clog::LOG_CTX
                     .scope(std::cell::RefCell::new(log_ctx), async move {
-                        let response = ctx.res_handle(precondition, defer, handlers, endpoint_key).await;
+                        let limited = match &limiter {
+                            Some(limiter) => limiter.check_request(&ctx).await,
+                            None => None,
+                        };
+                        let response = match limited {
+                            Some(response) => response,
+                            None => {
+                                let response = ctx.res_handle(precondition, defer, handlers, endpoint_key).await;
+                                let response = match ctx.idempotency_claim.take() {
+                                    Some(claim) => claim.finish(response, limit).await,
+                                    None => response,
+                                };
+                                if let Some(claim) = ctx.webhook_claim.take() {
+                                    claim.finish(response.status()).await;
+                                }
+                                response
+                            }
+                        };
+                        crate::metrics::fuse_request(endpoint_key, response.status().as_u16(), start_time.elapsed().as_millis() as i32);
+                        if !is_logged {
+                            return response;
+                        }
 
                         let (res_parts, res_body) = response.into_parts();
-                        let limit =
-                            std::env::var("RMOD_MAX_BODY_SIZE").ok().and_then(|s| s.parse::<usize>().ok()).unwrap_or(100 * 1024 * 1024);
-                        let res_bytes = axum::body::to_bytes(res_body, limit).await.unwrap_or_default();
+                        let status_code = res_parts.status.as_u16() as i32;
 
-                        if !is_excluded && clog_config.is_some() {
-                            let duration_ms = start_time.elapsed().as_millis() as i32;
-                            let status_code = res_parts.status.as_u16() as i32;
+                        let mut payload_map = serde_json::json!({
+                            "endpoint": endpoint_key,
+                            "path": path_clone,
+                        });
 
-                            let res_body_str = String::from_utf8_lossy(&res_bytes);
-                            let res_body_val = crate::clog::parse_body_to_json_val(&res_body_str);
-
-                            let mut payload_map = serde_json::json!({
-                                "endpoint": endpoint_key,
-                                "path": path_clone,
-                                "response_body": res_body_val,
-                            });
+                        if status_code >= 400 {
+                            if let Some(loc) = ctx.res_location {
+                                payload_map["location"] = serde_json::Value::String(format!("{}:{}", loc.file(), loc.line()));
+                            }
 
-                            if status_code >= 400 {
-                                if let Some(loc) = ctx.res_location {
-                                    payload_map["location"] = serde_json::Value::String(format!("{}:{}", loc.file(), loc.line()));
+                            if let Some(ref bt) = ctx.res_backtrace {
+                                let bt_str = format!("{}", bt);
+                                let clean_st = clog::clean_stacktrace(&bt_str);
+                                if !clean_st.trim().is_empty() {
+                                    payload_map["stacktrace"] = serde_json::Value::String(clean_st);
                                 }
+                            }
+
+                            if let Some(source) =
+                                ctx.res_body.as_ref().and_then(|b| b.downcast_ref::<FuseError>()).and_then(|e| e.source.as_ref())
+                            {
+                                payload_map["error_source"] = serde_json::Value::String(source.clone());
+                            }
 
-                                if let Some(ref bt) = ctx.res_backtrace {
-                                    let bt_str = format!("{}", bt);
-                                    let clean_st = clog::clean_stacktrace(&bt_str);
-                                    if !clean_st.trim().is_empty() {
-                                        payload_map["stacktrace"] = serde_json::Value::String(clean_st);
-                                    }
+                            if let Some((panic, source)) = &ctx.panicked {
+                                payload_map["panic"] = serde_json::json!({
+                                    "message": panic.message,
+                                    "source": source.name,
+                                    "handler_index": source.handler_index,
+                                });
+                                if let Some(location) = &panic.location {
+                                    payload_map["location"] = serde_json::Value::String(location.clone());
                                 }
                             }
 
-                            let payload_json = payload_map.to_string();
-                            let current_user_uid = crate::clog::get_current_ctx().and_then(|c| c.user_uid).unwrap_or_default();
-                            let current_partner_uid = crate::clog::get_current_ctx().and_then(|c| c.partner_uid).unwrap_or_default();
-                            let finish_now_us = crate::time::now_us();
-
-                            let (pod_ip, node_name) = clog::pod_info();
-                            let info_map = serde_json::json!({
-                                "pod_ip": pod_ip,
-                                "node_name": node_name,
-                            });
-
-                            crate::clog::push_log(crate::clog::LogEntry {
-                                uid: crate::uid::new(),
-                                timestamp_unix_us: finish_now_us,
-                                env_name,
-                                service_name,
-                                trace_id,
-                                parent_uid: endpoint_uid,
-                                user_uid: current_user_uid,
-                                partner_uid: current_partner_uid,
-                                log_type: "API_RESPONSE".to_string(),
-                                action_name: endpoint_key.to_string(),
-                                duration_ms,
-                                status_code,
-                                payload_json,
-                                pod_name: clog::pod_name(),
-                                info_json: info_map.to_string(),
-                            });
+                            if let Some((timeout, source)) = ctx.timed_out {
+                                payload_map["timeout"] = serde_json::json!({
+                                    "after_ms": timeout.as_millis() as u64,
+                                    "source": source.name,
+                                    "handler_index": source.handler_index,
+                                });
+                            }
                         }
 
+                        let current_user_uid = crate::clog::get_current_ctx().and_then(|c| c.user_uid).unwrap_or_default();
+                        let current_partner_uid = crate::clog::get_current_ctx().and_then(|c| c.partner_uid).unwrap_or_default();
+
+                        let (pod_ip, node_name) = clog::pod_info();
+                        let info_map = serde_json::json!({
+                            "pod_ip": pod_ip,
+                            "node_name": node_name,
+                        });
+
+                        let mut entry = crate::clog::LogEntry {
+                            uid: crate::uid::new(),
+                            timestamp_unix_us: 0,
+                            env_name,
+                            service_name,
+                            trace_id,
+                            parent_uid: endpoint_uid,
+                            user_uid: current_user_uid,
+                            partner_uid: current_partner_uid,
+                            log_type: "API_RESPONSE".to_string(),
+                            action_name: endpoint_key.to_string(),
+                            duration_ms: 0,
+                            status_code,
+                            payload_json: String::new(),
+                            pod_name: clog::pod_name(),
+                            info_json: info_map.to_string(),
+                        };
+
+                        let sse = res_parts.extensions.get::<Arc<r_context_sse::SseStats>>().cloned();
+                        if opt.stream || sse.is_some() {
+                            // Logged once the response body has been fully sent (or the client went away).
+                            let on_done = move |res_tap: &TapState| {
+                                if opt.stream {
+                                    payload_map["request_body"] = req_tap.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).to_json();
+                                }
+                                payload_map["response_body"] = match sse {
+                                    Some(sse) => sse.to_json(res_tap.total),
+                                    None => res_tap.to_json(),
+                                };
+                                entry.duration_ms = start_time.elapsed().as_millis() as i32;
+                                entry.timestamp_unix_us = crate::time::now_us();
+                                entry.payload_json = payload_map.to_string();
+                                crate::clog::push_log(entry);
+                            };
+
+                            let res_tap = Arc::new(Mutex::new(TapState::default()));
+                            let body = Body::from_stream(BodyTap::new(res_body, res_tap, Some(Box::new(on_done))));
+                            return axum::response::Response::from_parts(res_parts, body);
+                        }
+
+                        let res_bytes = axum::body::to_bytes(res_body, limit).await.unwrap_or_default();
+                        let res_body_str = String::from_utf8_lossy(&res_bytes);
+                        payload_map["response_body"] = crate::clog::parse_body_to_json_val(&res_body_str);
+
+                        entry.duration_ms = start_time.elapsed().as_millis() as i32;
+                        entry.timestamp_unix_us = crate::time::now_us();
+                        entry.payload_json = payload_map.to_string();
+                        crate::clog::push_log(entry);
+
                         axum::response::Response::from_parts(res_parts, Body::from(res_bytes))
                     })
                     .await
@@ -321,34 +548,188 @@ impl Fuse {
         }
     }
 
+    fn claim_route(&mut self, key: &'static str, methods: &[(&'static str, MethodFilter)], path: &str) -> Result<(), String> {
+        // axum rejects two routes that differ only in their parameter names, e.g. `/users/{id}` and `/users/{uid}`
+        let shape = path_shape(path);
+        if let Some(existing) = self.shapes.get(&shape)
+            && existing != path
+        {
+            return Err(format!("endpoint '{}' conflicts with path '{}'", key, existing));
+        }
+        if let Some(existing) = self.shapes.iter().find(|(s, _)| is_wildcard_conflict(s, &shape)).map(|(_, p)| p) {
+            return Err(format!("endpoint '{}' conflicts with path '{}'", key, existing));
+        }
+
+        for (name, _) in methods {
+            if let Some(existing) = self.routes.get(&format!("{} {}", name, shape)) {
+                return Err(format!("endpoint '{}' collides with '{}' on {} {}", key, existing, name, path));
+            }
+        }
+
+        self.shapes.insert(shape.clone(), path.to_string());
+        for (name, _) in methods {
+            self.routes.insert(format!("{} {}", name, shape), key);
+        }
+        Ok(())
+    }
+
+    /// Final router with the health and API docs routes mounted and the router-wide layers (CORS, compression, security headers) applied.
+    pub(crate) fn into_router(self) -> Router {
+        let router = fuse_health::mount(self.router, &self.shapes);
+        let router = fuse_openapi::mount(router, &self.shapes, &self.api_docs);
+        self.layers.apply(router)
+    }
+
     pub(crate) async fn run<F: FnOnce()>(self, addr: &str, on_start: Option<F>) {
+        self.exit_on_errors();
+
         crate::util::lifecycle::start();
-        let listener = match tokio::net::TcpListener::bind(addr).await {
-            Ok(l) => l,
-            Err(e) => {
-                tracing::error!("Failed to bind REST server to address '{}': {}", addr, e);
-                std::process::exit(1);
-            }
-        };
+        let listener = bind(addr, "REST").await;
         if let Some(f) = on_start {
             f();
         }
 
-        let mut shutdown_rx = crate::util::lifecycle::subscribe();
-        if let Err(e) = axum::serve(listener, self.router.into_make_service_with_connect_info::<SocketAddr>())
-            .with_graceful_shutdown(async move {
-                let _ = shutdown_rx.recv().await;
-            })
-            .await
-        {
-            tracing::error!("REST server failed: {}", e);
+        serve(listener, self.into_router(), "REST").await;
+        crate::util::lifecycle::wait().await;
+    }
+
+    pub(crate) fn exit_on_errors(&self) {
+        if !self.errors.is_empty() {
+            for e in &self.errors {
+                tracing::error!("Invalid REST server setup: {}", e);
+            }
             std::process::exit(1);
         }
+    }
+}
 
-        crate::util::lifecycle::wait().await;
+pub(crate) async fn bind(addr: &str, server: &str) -> tokio::net::TcpListener {
+    match tokio::net::TcpListener::bind(addr).await {
+        Ok(l) => l,
+        Err(e) => {
+            tracing::error!("Failed to bind {} server to address '{}': {}", server, addr, e);
+            std::process::exit(1);
+        }
     }
 }
 
+/// Serves `service` until shutdown starts, over TLS when it is configured (see `config::rest_tls`).
+pub(crate) async fn serve<S>(listener: tokio::net::TcpListener, service: S, server: &str)
+where
+    S: tower::Service<Request<Body>, Response = Response, Error = std::convert::Infallible> + Clone + Send + Sync + 'static,
+    S::Future: Send,
+{
+    let res = match fuse_tls::tls_source() {
+        Some(source) => match fuse_tls::TlsListener::new(listener, source) {
+            Ok(listener) => serve_with(listener, service).await,
+            Err(e) => {
+                tracing::error!("Invalid {} server TLS setup: {}", server, e);
+                std::process::exit(1);
+            }
+        },
+        None => serve_with(listener, service).await,
+    };
+    if let Err(e) = res {
+        tracing::error!("{} server failed: {}", server, e);
+        std::process::exit(1);
+    }
+}
+
+/// HTTP/1 and HTTP/2 are both accepted. `ConnectInfo<SocketAddr>` is added per connection here since axum only
+/// provides it for a plain `TcpListener`.
+async fn serve_with<L, S>(listener: L, service: S) -> std::io::Result<()>
+where
+    L: axum::serve::Listener<Addr = SocketAddr>,
+    S: tower::Service<Request<Body>, Response = Response, Error = std::convert::Infallible> + Clone + Send + Sync + 'static,
+    S::Future: Send,
+{
+    let make_service = tower::service_fn(move |stream: axum::serve::IncomingStream<'_, L>| {
+        let connect_info = axum::Extension(axum::extract::ConnectInfo(*stream.remote_addr()));
+        std::future::ready(Ok::<_, std::convert::Infallible>(tower::Layer::layer(&connect_info, service.clone())))
+    });
+
+    let mut shutdown_rx = crate::util::lifecycle::subscribe();
+    axum::serve(listener, make_service)
+        .with_graceful_shutdown(async move {
+            let _ = shutdown_rx.recv().await;
+        })
+        .await
+}
+
+type EndpointMethods = Vec<(&'static str, MethodFilter)>;
+
+const ANY_METHODS: [&str; 9] = ["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS", "TRACE", "CONNECT"];
+
+fn parse_method(name: &str) -> Option<(&'static str, MethodFilter)> {
+    match name {
+        "GET" => Some(("GET", MethodFilter::GET)),
+        "POS" | "POST" => Some(("POST", MethodFilter::POST)),
+        "PUT" => Some(("PUT", MethodFilter::PUT)),
+        "DEL" | "DELETE" => Some(("DELETE", MethodFilter::DELETE)),
+        "PAT" | "PATCH" => Some(("PATCH", MethodFilter::PATCH)),
+        "HEAD" => Some(("HEAD", MethodFilter::HEAD)),
+        "OPT" | "OPTIONS" => Some(("OPTIONS", MethodFilter::OPTIONS)),
+        "TRACE" => Some(("TRACE", MethodFilter::TRACE)),
+        "CONNECT" => Some(("CONNECT", MethodFilter::CONNECT)),
+        _ => None,
+    }
+}
+
+/// Parses an endpoint key such as `"GET: /users"`, `"GET|POST: /users"` or `"ANY: /users"`.
+pub(crate) fn parse_endpoint_key(key: &str) -> Result<(EndpointMethods, String), String> {
+    let Some((method_str, path)) = key.split_once(": ") else {
+        return Err(format!("endpoint '{}' must be in the form \"METHOD: /path\"", key));
+    };
+
+    let path = path.trim();
+    if !path.starts_with('/') {
+        return Err(format!("endpoint '{}' path must start with '/'", key));
+    }
+
+    let mut methods: EndpointMethods = Vec::new();
+    for name in method_str.split('|').map(|m| m.trim()) {
+        let parsed = if name == "ANY" {
+            ANY_METHODS.iter().filter_map(|m| parse_method(m)).collect()
+        } else {
+            match parse_method(name) {
+                Some(m) => vec![m],
+                None => return Err(format!("endpoint '{}' has unknown method '{}'", key, name)),
+            }
+        };
+
+        for m in parsed {
+            if methods.iter().any(|(n, _)| *n == m.0) {
+                return Err(format!("endpoint '{}' declares method {} more than once", key, m.0));
+            }
+            methods.push(m);
+        }
+    }
+
+    Ok((methods, path.to_string()))
+}
+
+fn path_shape(path: &str) -> String {
+    path.split('/')
+        .map(|seg| match seg.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(name) if name.starts_with('*') => "{*}",
+            Some(_) => "{}",
+            None => seg,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// axum also rejects a parameter and a catch-all at the same position, e.g. `/files/{id}` and `/files/{*path}`.
+fn is_wildcard_conflict(a: &str, b: &str) -> bool {
+    for (x, y) in a.split('/').zip(b.split('/')) {
+        if x == y {
+            continue;
+        }
+        return matches!((x, y), ("{}", "{*}") | ("{*}", "{}"));
+    }
+    false
+}
+
 impl FuseRContext {
     pub(crate) fn new(req: Request<Body>) -> Self {
         Self {
@@ -359,23 +740,27 @@ impl FuseRContext {
             res_backtrace: None,
             res_location: None,
             res_source: FuseResSource::new(""),
+            res_headers: HeaderMap::new(),
+            ws: None,
+            timeout: None,
+            idempotency: None,
+            idempotency_claim: None,
+            webhook_claim: None,
+            timed_out: None,
+            panicked: None,
+            running: FuseResSource::new(""),
 
             response: None,
             body: None,
         }
     }
 
-    #[inline(never)]
-    pub async fn res_handle(
-        &mut self,
-        precondition: Arc<Vec<FuseHandler>>,
-        defer: FuseHandler,
-        handlers: Arc<Vec<FuseHandler>>,
-        endpoint_key: &'static str,
-    ) -> Response {
+    /// Runs the preconditions, then the handlers, stopping at the first non-success response.
+    async fn run_chain(&mut self, precondition: &[FuseHandler], handlers: &[FuseHandler], endpoint_key: &'static str) {
         let mut break_next = false;
 
         for (i, h) in precondition.iter().enumerate() {
+            self.running = FuseResSource { name: "precondition", handler_index: i, endpoint_key };
             match h(self).await {
                 Ok((status, body)) => {
                     if !status.is_success() {
@@ -401,8 +786,29 @@ impl FuseRContext {
             }
         }
 
+        if !break_next && let Some(idempotency) = self.idempotency.take() {
+            // after the preconditions, so a replay is only served to a caller they let through
+            match idempotency.begin(self, endpoint_key).await {
+                fuse_idempotency::Idempotent::Skip => {}
+                fuse_idempotency::Idempotent::Respond(response) => {
+                    break_next = true;
+                    self.res_status = Some(response.status());
+                    self.res_source = FuseResSource { name: "idempotency", handler_index: 0, endpoint_key };
+                    self.response = Some(response);
+                }
+                fuse_idempotency::Idempotent::Reject(e) => {
+                    break_next = true;
+                    self.res_status = Some(e.status);
+                    self.res_body = Some(Arc::new(e));
+                    self.res_source = FuseResSource { name: "idempotency", handler_index: 0, endpoint_key };
+                }
+                fuse_idempotency::Idempotent::Run(claim) => self.idempotency_claim = Some(claim),
+            }
+        }
+
         if !break_next {
             for (i, h) in handlers.iter().enumerate() {
+                self.running = FuseResSource { name: "handler", handler_index: i, endpoint_key };
                 match h(self).await {
                     Ok((status, body)) => {
                         self.res_status = Some(status);
@@ -426,14 +832,84 @@ impl FuseRContext {
                 }
             }
         }
+    }
+
+    /// The chain was dropped mid-way: whatever it set on the response is discarded and defer sees a 504.
+    /// The location and backtrace of the last `ok`/`err` call are kept to show how far it got.
+    fn abort_on_timeout(&mut self, timeout: std::time::Duration, endpoint_key: &'static str) {
+        tracing::warn!(
+            "request timed out after {:?} [endpoint: {}, source: {}:{}]",
+            timeout,
+            endpoint_key,
+            self.running.name,
+            self.running.handler_index
+        );
+        self.response = None;
+        self.res_status = Some(StatusCode::GATEWAY_TIMEOUT);
+        self.res_body = Some(Arc::new(FuseError::new(StatusCode::GATEWAY_TIMEOUT, "gateway_timeout", "request timed out")));
+        if self.res_backtrace.is_none() {
+            self.res_backtrace = Some(Arc::new(Backtrace::force_capture()));
+        }
+        self.res_source = self.running;
+        self.timed_out = Some((timeout, self.running));
+    }
+
+    /// A stage panicked: the response becomes a 500 carrying the panic location and backtrace. A panic in the
+    /// chain still goes through defer; a panic in defer is the final response.
+    fn abort_on_panic(&mut self, mut panic: fuse_panic::FusePanic, endpoint_key: &'static str) {
+        tracing::error!(
+            "handler panicked: {} [endpoint: {}, source: {}:{}, at: {}]",
+            panic.message,
+            endpoint_key,
+            self.running.name,
+            self.running.handler_index,
+            panic.location.as_deref().unwrap_or_default()
+        );
+        self.response = None;
+        self.res_status = Some(StatusCode::INTERNAL_SERVER_ERROR);
+        self.res_body = Some(Arc::new(FuseError::internal("handler panicked")));
+        self.res_backtrace = Some(Arc::new(panic.backtrace.take().unwrap_or_else(Backtrace::force_capture)));
+        self.res_source = self.running;
+        self.panicked = Some((panic, self.running));
+    }
+
+    pub fn is_panicked(&self) -> bool {
+        self.panicked.is_some()
+    }
+
+    /// Whether the handler chain was aborted by the endpoint timeout; the defer handler still runs after it.
+    pub fn is_timed_out(&self) -> bool {
+        self.timed_out.is_some()
+    }
+
+    #[inline(never)]
+    pub async fn res_handle(
+        &mut self,
+        precondition: Arc<Vec<FuseHandler>>,
+        defer: FuseHandler,
+        handlers: Arc<Vec<FuseHandler>>,
+        endpoint_key: &'static str,
+    ) -> Response {
+        let timeout = self.timeout.filter(|t| !t.is_zero());
+        let chain = fuse_panic::catch(self.run_chain(&precondition, &handlers, endpoint_key));
+        let chain_result = match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, chain).await.map_err(|_| timeout),
+            None => Ok(chain.await),
+        };
+        match chain_result {
+            Ok(Ok(())) => {}
+            Ok(Err(panic)) => self.abort_on_panic(panic, endpoint_key),
+            Err(timeout) => self.abort_on_timeout(timeout, endpoint_key),
+        }
 
-        match defer(self).await {
-            Ok((status, body)) => {
+        self.running = FuseResSource { name: "defer", handler_index: 0, endpoint_key };
+        match fuse_panic::catch(defer(self)).await {
+            Ok(Ok((status, body))) => {
                 self.res_status = Some(status);
                 self.res_body = Some(body);
                 self.res_source = FuseResSource { name: "defer", handler_index: 0, endpoint_key };
             }
-            Err((status, body)) => {
+            Ok(Err((status, body))) => {
                 self.res_status = Some(status);
                 self.res_body = Some(body.clone());
                 if self.res_backtrace.is_none() {
@@ -441,28 +917,41 @@ impl FuseRContext {
                 }
                 self.res_source = FuseResSource { name: "defer", handler_index: 0, endpoint_key };
             }
+            Err(panic) => self.abort_on_panic(panic, endpoint_key),
         }
 
-        if let (Some(status), Some(body)) = (self.res_status, &self.res_body)
+        if let (Some(status), Some(body)) = (self.res_status, self.res_body.clone())
             && self.response.is_none()
         {
-            if let Some(text) = body.downcast_ref::<String>() {
-                let trimmed = text.trim();
-                if trimmed.starts_with("<") {
-                    self.response = Some((status, axum::response::Html(text.clone())).into_response());
-                } else {
-                    self.response = Some((status, text.clone()).into_response());
-                }
-            } else if let Some(text) = body.downcast_ref::<&'static str>() {
-                self.response = Some((status, (*text).to_string()).into_response());
-            } else if let Some(json) = body.downcast_ref::<serde_json::Value>() {
-                self.response = Some((status, axum::Json(json.clone())).into_response());
-            } else if let Some(bytes) = body.downcast_ref::<Vec<u8>>() {
-                self.response = Some((status, bytes.clone()).into_response());
-            }
+            self.response = match r_context_body::body_to_response(status, body.as_ref()) {
+                Some(res) => Some(res),
+                None => Some(self.no_body_converter(endpoint_key)),
+            };
         }
 
-        self.response.take().unwrap_or_else(|| StatusCode::NOT_FOUND.into_response())
+        let mut response = self.response.take().unwrap_or_else(|| StatusCode::NOT_FOUND.into_response());
+        r_context_header::merge_headers(&mut response, std::mem::take(&mut self.res_headers));
+        response
+    }
+
+    fn no_body_converter(&mut self, endpoint_key: &'static str) -> Response {
+        let message = "response body type has no converter, register one with fuse::add_body_converter";
+        tracing::error!("{} [endpoint: {}, source: {}:{}]", message, endpoint_key, self.res_source.name, self.res_source.handler_index);
+        clog::error(
+            endpoint_key,
+            serde_json::json!({
+                "error": message,
+                "source": self.res_source.name,
+                "handler_index": self.res_source.handler_index,
+                "status": self.res_status.map(|s| s.as_u16()),
+            }),
+        );
+
+        self.res_status = Some(StatusCode::INTERNAL_SERVER_ERROR);
+        if self.res_backtrace.is_none() {
+            self.res_backtrace = Some(Arc::new(Backtrace::force_capture()));
+        }
+        FuseError::internal(message).to_response()
     }
 
     pub fn body_text(&self) -> String {
@@ -473,9 +962,15 @@ impl FuseRContext {
             if let Some(s) = body.downcast_ref::<&'static str>() {
                 return (*s).to_string();
             }
+            if let Some(html) = body.downcast_ref::<axum::response::Html<String>>() {
+                return html.0.clone();
+            }
             if let Some(e) = body.downcast_ref::<sqlx::Error>() {
                 return e.to_string();
             }
+            if let Some(e) = body.downcast_ref::<FuseError>() {
+                return e.message.clone();
+            }
         }
         "".to_string()
     }
@@ -609,12 +1104,7 @@ impl FuseRContext {
     }
 
     pub fn json_parse<T: serde::de::DeserializeOwned>(&self) -> Result<T, String> {
-        self.json::<T>().map_err(|e| {
-            let msg_str = e.to_string();
-            let msg = msg_str.split(" at line ").next().unwrap_or(&msg_str);
-            let msg = msg.split(" at column ").next().unwrap_or(msg);
-            msg.to_string()
-        })
+        self.json::<T>().map_err(|e| r_context_validate::strip_position(&e.to_string()))
     }
 
     pub fn query(&self) -> HashMap<String, String> {
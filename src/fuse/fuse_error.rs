@@ -0,0 +1,275 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::{FuseRContext, FuseResult};
+use crate::clog;
+use crate::validate::FieldError;
+use axum::http::{HeaderValue, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use prost::Message;
+use serde_json::Value;
+use std::any::Any;
+use std::sync::Arc;
+
+/// Error of a fuse handler or gRPC method. Rendered as RFC 7807 `application/problem+json` in fuse and as a
+/// `tonic::Status` in gRPC, both carrying the trace id of the request so the clog trace can be found.
+#[derive(Clone, Debug)]
+pub struct FuseError {
+    pub status: StatusCode,
+    pub code: String,
+    pub message: String,
+    pub details: Option<Value>,
+    /// Underlying error, written to the `API_RESPONSE` log but never sent to the client.
+    pub source: Option<String>,
+}
+
+impl FuseError {
+    pub fn new(status: StatusCode, code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { status, code: code.into(), message: message.into(), details: None, source: None }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "bad_request", message)
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "unauthorized", message)
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, "forbidden", message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, "not_found", message)
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::CONFLICT, "conflict", message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "internal_server_error", message)
+    }
+
+    /// For the errors of `lock::dist` and `lock::dist_many`: `lock::dist(key, None).await.map_err(FuseError::lock_unavailable)`.
+    pub fn lock_unavailable(e: impl ToString) -> Self {
+        Self::new(StatusCode::CONFLICT, "lock_unavailable", "resource is busy, retry later").with_source(e)
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn with_source(mut self, source: impl ToString) -> Self {
+        self.source = Some(source.to_string());
+        self
+    }
+
+    /// The RFC 7807 body; `trace_id` is taken from the clog context of the current request.
+    pub fn to_problem(&self) -> Value {
+        let mut problem = serde_json::json!({
+            "type": "about:blank",
+            "title": self.status.canonical_reason().unwrap_or_default(),
+            "status": self.status.as_u16(),
+            "detail": self.message,
+            "code": self.code,
+            "trace_id": current_trace_id(),
+        });
+        if let Some(details) = &self.details {
+            problem["details"] = details.clone();
+        }
+        problem
+    }
+
+    pub(crate) fn to_response(&self) -> Response {
+        let mut response = (self.status, axum::Json(self.to_problem())).into_response();
+        let headers = response.headers_mut();
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/problem+json"));
+        if let Some(trace_id) = current_trace_id().and_then(|t| HeaderValue::from_str(&t).ok()) {
+            headers.insert("x-trace-id", trace_id);
+        }
+        response
+    }
+}
+
+impl std::fmt::Display for FuseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for FuseError {}
+
+fn current_trace_id() -> Option<String> {
+    clog::get_current_ctx().map(|c| c.trace_id).filter(|t| !t.is_empty())
+}
+
+impl From<sqlx::Error> for FuseError {
+    fn from(e: sqlx::Error) -> Self {
+        let err = match &e {
+            sqlx::Error::RowNotFound => Self::not_found("record not found"),
+            sqlx::Error::Database(db) => match db.code().as_deref() {
+                Some("23505") => Self::conflict("record already exists"),
+                Some("23503") => Self::new(StatusCode::CONFLICT, "conflict", "record is referenced by or references another record"),
+                Some("40001") | Some("40P01") => {
+                    Self::new(StatusCode::SERVICE_UNAVAILABLE, "database_busy", "the request conflicted with another one, retry it")
+                }
+                _ => Self::new(StatusCode::INTERNAL_SERVER_ERROR, "database_error", "database error"),
+            },
+            sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed => {
+                Self::new(StatusCode::SERVICE_UNAVAILABLE, "database_unavailable", "database is unavailable")
+            }
+            _ => Self::new(StatusCode::INTERNAL_SERVER_ERROR, "database_error", "database error"),
+        };
+        err.with_source(e)
+    }
+}
+
+impl From<reqwest::Error> for FuseError {
+    fn from(e: reqwest::Error) -> Self {
+        let err = if e.is_timeout() {
+            Self::new(StatusCode::GATEWAY_TIMEOUT, "upstream_timeout", "upstream service timed out")
+        } else if let Some(status) = e.status() {
+            Self::new(StatusCode::BAD_GATEWAY, "upstream_error", "upstream service returned an error")
+                .with_details(serde_json::json!({ "upstream_status": status.as_u16() }))
+        } else {
+            Self::new(StatusCode::BAD_GATEWAY, "upstream_unavailable", "upstream service is unavailable")
+        };
+        err.with_source(e)
+    }
+}
+
+impl From<Vec<FieldError>> for FuseError {
+    fn from(errors: Vec<FieldError>) -> Self {
+        Self::new(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed", "request body is invalid")
+            .with_details(serde_json::json!({ "fields": errors }))
+    }
+}
+
+impl From<FuseError> for tonic::Status {
+    fn from(e: FuseError) -> Self {
+        let code = match e.status {
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => tonic::Code::InvalidArgument,
+            StatusCode::UNAUTHORIZED => tonic::Code::Unauthenticated,
+            StatusCode::FORBIDDEN => tonic::Code::PermissionDenied,
+            StatusCode::NOT_FOUND => tonic::Code::NotFound,
+            StatusCode::CONFLICT => tonic::Code::Aborted,
+            StatusCode::PRECONDITION_FAILED => tonic::Code::FailedPrecondition,
+            StatusCode::TOO_MANY_REQUESTS => tonic::Code::ResourceExhausted,
+            StatusCode::NOT_IMPLEMENTED => tonic::Code::Unimplemented,
+            StatusCode::SERVICE_UNAVAILABLE | StatusCode::BAD_GATEWAY => tonic::Code::Unavailable,
+            StatusCode::GATEWAY_TIMEOUT => tonic::Code::DeadlineExceeded,
+            s if s.is_client_error() => tonic::Code::FailedPrecondition,
+            _ => tonic::Code::Internal,
+        };
+
+        let details = bytes::Bytes::from(rpc_status(code, &e).encode_to_vec());
+        let mut metadata = tonic::metadata::MetadataMap::new();
+        if let Some(trace_id) = current_trace_id().and_then(|t| t.parse().ok()) {
+            metadata.insert("x-trace-id", trace_id);
+        }
+        if let Ok(code) = e.code.parse() {
+            metadata.insert("x-error-code", code);
+        }
+        tonic::Status::with_details_and_metadata(code, e.message, details, metadata)
+    }
+}
+
+/// `google.rpc.Status` of the `grpc-status-details-bin` trailer, as in `google/rpc/status.proto`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct RpcStatus {
+    #[prost(int32, tag = "1")]
+    pub(crate) code: i32,
+    #[prost(string, tag = "2")]
+    pub(crate) message: String,
+    #[prost(message, repeated, tag = "3")]
+    pub(crate) details: Vec<RpcAny>,
+}
+
+/// `google.protobuf.Any`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct RpcAny {
+    #[prost(string, tag = "1")]
+    pub(crate) type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub(crate) value: Vec<u8>,
+}
+
+/// `google.rpc.ErrorInfo`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct RpcErrorInfo {
+    #[prost(string, tag = "1")]
+    pub(crate) reason: String,
+    #[prost(string, tag = "2")]
+    pub(crate) domain: String,
+    #[prost(map = "string, string", tag = "3")]
+    pub(crate) metadata: std::collections::HashMap<String, String>,
+}
+
+/// `google.rpc.RequestInfo`.
+#[derive(Clone, PartialEq, prost::Message)]
+struct RpcRequestInfo {
+    #[prost(string, tag = "1")]
+    request_id: String,
+    #[prost(string, tag = "2")]
+    serving_data: String,
+}
+
+/// Standard gRPC rich error: the code as `ErrorInfo.reason`, `details` as JSON in its metadata and the trace id
+/// as `RequestInfo.request_id`, decodable with e.g. `tonic-types`.
+fn rpc_status(code: tonic::Code, e: &FuseError) -> RpcStatus {
+    let mut metadata = std::collections::HashMap::new();
+    if let Some(details) = &e.details {
+        metadata.insert("details".to_string(), details.to_string());
+    }
+    let domain = clog::get_config().map(|c| c.service_name.clone()).unwrap_or_default();
+    let error_info = RpcErrorInfo { reason: e.code.clone(), domain, metadata };
+    let mut details = vec![RpcAny { type_url: "type.googleapis.com/google.rpc.ErrorInfo".to_string(), value: error_info.encode_to_vec() }];
+    if let Some(trace_id) = current_trace_id() {
+        let request_info = RpcRequestInfo { request_id: trace_id, serving_data: String::new() };
+        details.push(RpcAny { type_url: "type.googleapis.com/google.rpc.RequestInfo".to_string(), value: request_info.encode_to_vec() });
+    }
+    RpcStatus { code: code as i32, message: e.message.clone(), details }
+}
+
+impl FuseRContext {
+    /// Responds with `e` as problem details, e.g. `return ctx.fail(FuseError::not_found("user not found"))`.
+    #[inline(never)]
+    #[track_caller]
+    pub fn fail(&mut self, e: impl Into<FuseError>) -> FuseResult {
+        let e = e.into();
+        self.err(e.status, e)
+    }
+
+    #[inline(never)]
+    #[track_caller]
+    pub fn fail_val(&mut self, e: impl Into<FuseError>) -> (StatusCode, Arc<dyn Any + Send + Sync>) {
+        let e = e.into();
+        self.err_val(e.status, e)
+    }
+}
+
+/// `?` for errors convertible into `FuseError`: `let user = repo::get(id).await.or_fail(ctx)?;`.
+pub trait FuseErrorExt<T> {
+    fn or_fail(self, ctx: &mut FuseRContext) -> Result<T, (StatusCode, Arc<dyn Any + Send + Sync>)>;
+}
+
+impl<T, E: Into<FuseError>> FuseErrorExt<T> for Result<T, E> {
+    #[inline(never)]
+    #[track_caller]
+    fn or_fail(self, ctx: &mut FuseRContext) -> Result<T, (StatusCode, Arc<dyn Any + Send + Sync>)> {
+        match self {
+            Ok(v) => Ok(v),
+            Err(e) => Err(ctx.fail_val(e)),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::{Fuse, FuseHandler, FuseOptions, FuseWsHandler};
+use std::collections::HashMap;
+
+/// Endpoints sharing a path prefix, a precondition chain and a defer handler.
+pub struct FuseGroup<'a> {
+    fuse: &'a mut Fuse,
+    prefix: String,
+    defer: FuseHandler,
+    precondition: Vec<FuseHandler>,
+}
+
+impl Fuse {
+    /// Registers a group of endpoints under `prefix`, e.g. `fuse.group("/admin", defer, fuse_handlers!(auth), |g| { ... })`.
+    pub fn group<F>(&mut self, prefix: &str, defer: FuseHandler, precondition: Vec<FuseHandler>, f: F)
+    where
+        F: FnOnce(&mut FuseGroup),
+    {
+        let mut group = FuseGroup { fuse: self, prefix: normalize_prefix(prefix), defer, precondition };
+        f(&mut group);
+    }
+}
+
+impl FuseGroup<'_> {
+    /// Registers endpoints whose keys are relative to the group prefix; `"GET: /users"` in group `/admin`
+    /// becomes `"GET: /admin/users"`, which is also the key used for clog and `Fuse::option`.
+    pub fn endpoints(&mut self, mapping: HashMap<&'static str, Vec<FuseHandler>>) {
+        self.fuse.register(&self.prefix, self.defer, self.precondition.clone(), mapping);
+    }
+
+    /// Registers WebSocket endpoints whose keys are relative to the group prefix.
+    pub fn ws_endpoints(&mut self, mapping: HashMap<&'static str, FuseWsHandler>) {
+        self.fuse.register_ws(&self.prefix, self.defer, self.precondition.clone(), mapping);
+    }
+
+    /// Sets the options of an endpoint key relative to the group prefix.
+    pub fn option(&mut self, endpoint_key: &'static str, opt: FuseOptions) {
+        let key = prefixed_key(&self.prefix, endpoint_key);
+        self.fuse.option(key, opt);
+    }
+
+    /// Nests a group: its preconditions run after this group's, and `defer` replaces this group's defer when set.
+    pub fn group<F>(&mut self, prefix: &str, defer: Option<FuseHandler>, precondition: Vec<FuseHandler>, f: F)
+    where
+        F: FnOnce(&mut FuseGroup),
+    {
+        let mut chain = self.precondition.clone();
+        chain.extend(precondition);
+
+        let mut group = FuseGroup {
+            fuse: &mut *self.fuse,
+            prefix: format!("{}{}", self.prefix, normalize_prefix(prefix)),
+            defer: defer.unwrap_or(self.defer),
+            precondition: chain,
+        };
+        f(&mut group);
+    }
+}
+
+pub(crate) fn normalize_prefix(prefix: &str) -> String {
+    let trimmed = prefix.trim().trim_end_matches('/');
+    if trimmed.is_empty() || trimmed.starts_with('/') { trimmed.to_string() } else { format!("/{}", trimmed) }
+}
+
+/// Joins a group prefix into an endpoint key. The joined key lives for the rest of the process,
+/// like the key literals passed to `Fuse::endpoints`.
+pub(crate) fn prefixed_key(prefix: &str, key: &'static str) -> &'static str {
+    if prefix.is_empty() {
+        return key;
+    }
+
+    let Some((method, path)) = key.split_once(": ") else {
+        return key;
+    };
+
+    let path = path.trim();
+    if !path.starts_with('/') {
+        return key;
+    }
+
+    let path = if path == "/" { "" } else { path };
+    Box::leak(format!("{}: {}{}", method, prefix, path).into_boxed_str())
+}
@@ -121,6 +121,22 @@ where
                 });
             }
 
+            if let Some(limiter) = crate::fuse::grpc_limiter(&path) {
+                let remote = req_reconstructed
+                    .extensions()
+                    .get::<tonic::transport::server::TcpConnectInfo>()
+                    .and_then(|i| i.remote_addr())
+                    .map(|a| a.ip());
+                let check = limiter.check(req_reconstructed.headers(), remote);
+                if let Some(retry_after) = clog::LOG_CTX.scope(std::cell::RefCell::new(log_ctx.clone()), check).await {
+                    crate::metrics::grpc_server(&path, "8", start_time.elapsed().as_millis() as i32);
+                    let mut response = tonic::Status::resource_exhausted("rate limit exceeded").into_http();
+                    let retry_after = crate::fuse::retry_after_secs(retry_after);
+                    response.headers_mut().insert("retry-after", tonic::codegen::http::HeaderValue::from(retry_after));
+                    return Ok(response);
+                }
+            }
+
             use tower::ServiceExt;
             match inner.ready().await {
                 Ok(ready_svc) => {
@@ -136,6 +152,9 @@ where
                                         res_parts.headers.get("grpc-status").and_then(|v| v.to_str().ok()).unwrap_or("0").to_string();
 
                                     let status_code = if grpc_status == "0" || res_parts.status.is_success() { 200 } else { 500 };
+                                    if !is_health_check {
+                                        crate::metrics::grpc_server(&path, &grpc_status, duration_ms);
+                                    }
 
                                     let res_axum_body = axum::body::Body::new(res_body);
                                     let res_bytes = axum::body::to_bytes(res_axum_body, limit).await.unwrap_or_default();
@@ -286,6 +305,9 @@ where
                 Ok(ready_svc) => ready_svc.call(req_reconstructed).await,
                 Err(err) => {
                     let duration_ms = start_time.elapsed().as_millis() as i32;
+                    if !is_health_check {
+                        crate::metrics::grpc_client(&path, "error", duration_ms);
+                    }
                     if !is_excluded && clog_config.is_some() {
                         let bt = std::backtrace::Backtrace::force_capture();
                         let bt_str = format!("{}", bt);
@@ -340,6 +362,9 @@ where
                     let grpc_status = res_parts.headers.get("grpc-status").and_then(|v| v.to_str().ok()).unwrap_or("0").to_string();
 
                     let status_code = if grpc_status == "0" || res_parts.status.is_success() { 200 } else { 500 };
+                    if !is_health_check {
+                        crate::metrics::grpc_client(&path, &grpc_status, duration_ms);
+                    }
 
                     let res_axum_body = axum::body::Body::new(res_body);
                     let res_bytes = axum::body::to_bytes(res_axum_body, limit).await.unwrap_or_default();
@@ -402,6 +427,9 @@ where
                 }
                 Err(err) => {
                     let duration_ms = start_time.elapsed().as_millis() as i32;
+                    if !is_health_check {
+                        crate::metrics::grpc_client(&path, "error", duration_ms);
+                    }
                     if !is_excluded && clog_config.is_some() {
                         let bt = std::backtrace::Backtrace::force_capture();
                         let bt_str = format!("{}", bt);
@@ -468,6 +496,10 @@ where
         tracing::error!("Failed to parse gRPC bind address '{}': {}", addr, e);
         std::process::exit(1);
     });
+    if let Err(e) = crate::fuse::validate_grpc_limits() {
+        tracing::error!("Invalid gRPC server setup: {}", e);
+        std::process::exit(1);
+    }
     crate::util::lifecycle::start();
     let mut shutdown_rx = crate::util::lifecycle::subscribe();
 
@@ -475,15 +507,8 @@ where
         f();
     }
 
-    let (mut health_reporter, health_service) = tonic_health::server::health_reporter();
-    health_reporter.set_serving::<S>().await;
-    health_reporter.set_service_status("", tonic_health::ServingStatus::Serving).await;
-
-    let wrapped_service = ClogGrpcService { inner: service.clone() };
-
     if let Err(e) = Server::builder()
-        .add_service(health_service)
-        .add_service(wrapped_service)
+        .add_routes(grpc_routes(service).await)
         .serve_with_shutdown(addr, async move {
             let _ = shutdown_rx.recv().await;
         })
@@ -496,6 +521,26 @@ where
     crate::util::lifecycle::wait().await;
 }
 
+/// The service wrapped in `ClogGrpcService`, next to the standard health service reporting it as serving.
+pub(crate) async fn grpc_routes<S>(service: S) -> tonic::service::Routes
+where
+    S: tonic::codegen::Service<
+            tonic::codegen::http::Request<tonic::body::BoxBody>,
+            Response = tonic::codegen::http::Response<tonic::body::BoxBody>,
+            Error = std::convert::Infallible,
+        > + tonic::server::NamedService
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+{
+    let (mut health_reporter, health_service) = tonic_health::server::health_reporter();
+    health_reporter.set_serving::<S>().await;
+    health_reporter.set_service_status("", tonic_health::ServingStatus::Serving).await;
+
+    tonic::service::Routes::new(health_service).add_service(ClogGrpcService { inner: service })
+}
+
 use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::sync::OnceLock;
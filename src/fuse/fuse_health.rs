@@ -0,0 +1,101 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use axum::Router;
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+static READYZ_TIMEOUT: OnceLock<Duration> = OnceLock::new();
+static METRICS_ENABLED: OnceLock<bool> = OnceLock::new();
+
+/// Time each readiness check may take, from `RMOD_READYZ_TIMEOUT` (e.g. `2s`, the default).
+fn readyz_timeout() -> Duration {
+    *READYZ_TIMEOUT.get_or_init(|| crate::time::to_duration(&crate::util::env::string_or("RMOD_READYZ_TIMEOUT", "2s")))
+}
+
+/// Whether `/metrics` is served, from `RMOD_METRICS` (default `true`).
+fn metrics_enabled() -> bool {
+    *METRICS_ENABLED.get_or_init(|| crate::util::env::bool_or("RMOD_METRICS", true))
+}
+
+/// Adds `/healthz`, `/livez`, `/readyz` and `/metrics` unless the service registered the path itself. They are
+/// plain routes outside the fuse handler chain, so probes and scrapes never reach clog or the request metrics.
+pub(crate) fn mount(mut router: Router, shapes: &HashMap<String, String>) -> Router {
+    for path in ["/healthz", "/livez"] {
+        if !shapes.contains_key(path) {
+            router = router.route(path, get(live));
+        }
+    }
+    if !shapes.contains_key("/readyz") {
+        router = router.route("/readyz", get(ready));
+        crate::util::lifecycle::serve_readiness();
+    }
+    if metrics_enabled() && !shapes.contains_key("/metrics") {
+        router = router.route("/metrics", get(metrics));
+    }
+    router
+}
+
+async fn live() -> Response {
+    (StatusCode::OK, axum::Json(serde_json::json!({ "status": "ok" }))).into_response()
+}
+
+async fn metrics() -> Response {
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], crate::metrics::render()).into_response()
+}
+
+/// Not ready as soon as shutdown starts, while the listeners still accept for `RMOD_READY_DRAIN`, so load
+/// balancers stop routing here before the servers close; otherwise ready when every db pool, the dist-lock
+/// backend and the central-log channel respond.
+async fn ready() -> Response {
+    if crate::util::lifecycle::is_shutting_down() {
+        return (StatusCode::SERVICE_UNAVAILABLE, axum::Json(serde_json::json!({ "status": "shutting_down" }))).into_response();
+    }
+
+    let timeout = readyz_timeout();
+    let mut checks = serde_json::Map::new();
+    let mut is_ready = true;
+    let mut record = |name: String, res: Result<(), String>| {
+        let val = match res {
+            Ok(_) => "ok".to_string(),
+            Err(e) => {
+                is_ready = false;
+                e
+            }
+        };
+        checks.insert(name, serde_json::Value::String(val));
+    };
+
+    match tokio::time::timeout(timeout, crate::store::db_ping_all()).await {
+        Ok(results) => {
+            for (key, res) in results {
+                record(format!("db:{}", key), res);
+            }
+        }
+        Err(_) => record("db".to_string(), Err("timed out".to_string())),
+    }
+
+    match tokio::time::timeout(timeout, crate::lock::dist_ping()).await {
+        Ok(Some(res)) => record("dist_lock".to_string(), res),
+        Ok(None) => {}
+        Err(_) => record("dist_lock".to_string(), Err("timed out".to_string())),
+    }
+
+    if let Some(res) = crate::clog::channel_status() {
+        record("clog".to_string(), res);
+    }
+
+    let (status, label) = if is_ready { (StatusCode::OK, "ready") } else { (StatusCode::SERVICE_UNAVAILABLE, "not_ready") };
+    (status, axum::Json(serde_json::json!({ "status": label, "checks": checks }))).into_response()
+}
@@ -0,0 +1,308 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::fuse_rate_limit::RedisBackend;
+use super::{FuseError, FuseRContext};
+use crate::clog;
+use crate::config::RedisLockConfig;
+use axum::body::{Body, HttpBody};
+use axum::http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header};
+use axum::response::Response;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Postgres};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+const HEADER: &str = "idempotency-key";
+const MAX_KEY_LEN: usize = 255;
+const EXPIRED_CLEANUP_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Replays the first response of an `Idempotency-Key` for the repeats within `ttl`. Responses with a 5xx status
+/// are not kept, so a retry after a server error runs the endpoint again.
+#[derive(Clone, Debug)]
+pub struct FuseIdempotency {
+    ttl: Duration,
+    required: bool,
+}
+
+/// Keeps the responses for `ttl`; the store is set up with `config::idempotency_db` or `config::idempotency_redis`.
+pub fn idempotency(ttl: Duration) -> FuseIdempotency {
+    FuseIdempotency { ttl, required: false }
+}
+
+impl FuseIdempotency {
+    /// Rejects requests without an `Idempotency-Key` header with 400 instead of running them unprotected.
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.ttl.is_zero() {
+            return Err("idempotency ttl must be greater than 0".to_string());
+        }
+        if STORE.get().is_none() {
+            return Err("idempotency store not initialized, call config::idempotency_db or config::idempotency_redis first".to_string());
+        }
+        if !crate::lock::is_dist_initialized() {
+            return Err("idempotency needs the dist lock, call config::pg_lock or config::redis_lock first".to_string());
+        }
+        Ok(())
+    }
+
+    /// Decides what to do with the request once the preconditions passed. Only owned data is kept across the
+    /// awaits, the request body is not `Sync`.
+    pub(crate) fn begin(&self, ctx: &FuseRContext, endpoint_key: &'static str) -> impl Future<Output = Idempotent> + Send + 'static {
+        let key = ctx.req.headers().get(HEADER).map(|v| v.to_str().map(|s| s.trim().to_string()));
+        let fingerprint = fingerprint(ctx);
+        let (ttl, required) = (self.ttl, self.required);
+
+        async move {
+            let key = match key {
+                None if required => {
+                    let message = "Idempotency-Key header is required";
+                    return Idempotent::Reject(FuseError::new(StatusCode::BAD_REQUEST, "idempotency_key_required", message));
+                }
+                None => return Idempotent::Skip,
+                Some(Ok(key)) if !key.is_empty() && key.len() <= MAX_KEY_LEN => key,
+                Some(_) => {
+                    let message = format!("Idempotency-Key must be 1 to {} visible characters", MAX_KEY_LEN);
+                    return Idempotent::Reject(FuseError::new(StatusCode::BAD_REQUEST, "invalid_idempotency_key", message));
+                }
+            };
+
+            // scoped to the endpoint and the caller the preconditions authenticated, so two partners or users never
+            // see each other's responses
+            let (partner_uid, user_uid) = clog::get_current_ctx().map(|c| (c.partner_uid, c.user_uid)).unwrap_or_default();
+            let (partner_uid, user_uid) = (partner_uid.unwrap_or_default(), user_uid.unwrap_or_default());
+            let store_key = format!("{:x}", Sha256::digest(format!("{}\n{}\n{}\n{}", endpoint_key, partner_uid, user_uid, key)));
+
+            // held until the response is stored, a concurrent repeat waits for it and then replays
+            let lock = match crate::lock::dist(&format!("rmod:idempotency:{}", store_key), None).await {
+                Ok(lock) => lock,
+                Err(e) => {
+                    tracing::warn!("idempotency lock for '{}' failed: {}", endpoint_key, e);
+                    let message = "a request with this Idempotency-Key is in progress";
+                    return Idempotent::Reject(FuseError::new(StatusCode::CONFLICT, "idempotency_key_in_use", message).with_source(e));
+                }
+            };
+
+            match load(&store_key).await {
+                Ok(Some(stored)) if stored.fingerprint != fingerprint => {
+                    let message = "Idempotency-Key was already used with a different request";
+                    Idempotent::Reject(FuseError::new(StatusCode::UNPROCESSABLE_ENTITY, "idempotency_key_reused", message))
+                }
+                Ok(Some(stored)) => Idempotent::Respond(stored.into_response()),
+                Ok(None) => Idempotent::Run(Box::new(Claim { store_key, fingerprint, ttl, _lock: lock })),
+                Err(e) => {
+                    // without the store a retry could run twice, so the request is not run at all
+                    tracing::error!("idempotency lookup for '{}' failed: {}", endpoint_key, e);
+                    let message = "idempotency store is unavailable";
+                    Idempotent::Reject(FuseError::new(StatusCode::SERVICE_UNAVAILABLE, "idempotency_unavailable", message).with_source(e))
+                }
+            }
+        }
+    }
+}
+
+pub(crate) enum Idempotent {
+    /// No `Idempotency-Key`, the request runs as usual.
+    Skip,
+    /// Replay of the stored response.
+    Respond(Response),
+    /// The request is not run, answered like a failed precondition.
+    Reject(FuseError),
+    Run(Box<Claim>),
+}
+
+/// First request of a key, holding its lock until the response is stored.
+pub(crate) struct Claim {
+    store_key: String,
+    fingerprint: String,
+    ttl: Duration,
+    _lock: crate::lock::DistLock,
+}
+
+impl Claim {
+    /// Stores the response unless it is a 5xx, SSE, an `ok_stream` body or larger than `limit`; those are returned
+    /// untouched and a repeat runs the endpoint again.
+    pub(crate) async fn finish(self, response: Response, limit: usize) -> Response {
+        let is_sse = response.extensions().get::<std::sync::Arc<super::r_context_sse::SseStats>>().is_some();
+        // buffered bodies know their size, streamed ones do not
+        let is_buffered = response.body().size_hint().exact().is_some_and(|size| size <= limit as u64);
+        if response.status().is_server_error() || is_sse || !is_buffered {
+            return response;
+        }
+
+        let (parts, body) = response.into_parts();
+        let bytes = match axum::body::to_bytes(body, limit).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                tracing::error!("idempotency could not read the response body, it is not stored: {}", e);
+                return FuseError::internal("response body could not be read").with_source(e).to_response();
+            }
+        };
+        let stored = StoredResponse::new(&self.fingerprint, parts.status, &parts.headers, &bytes);
+        if let Err(e) = save(&self.store_key, &stored, self.ttl).await {
+            tracing::warn!("idempotency store failed, repeats of this request will run again: {}", e);
+        }
+        Response::from_parts(parts, Body::from(bytes))
+    }
+}
+
+/// Method, path, query and body: a repeat must send the same request to get the stored response.
+fn fingerprint(ctx: &FuseRContext) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(ctx.req.method().as_str());
+    hasher.update(b"\n");
+    hasher.update(ctx.req.uri().path_and_query().map(|p| p.as_str()).unwrap_or_default());
+    hasher.update(b"\n");
+    hasher.update(ctx.body.as_deref().unwrap_or_default());
+    format!("{:x}", hasher.finalize())
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct StoredResponse {
+    fingerprint: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+    /// base64
+    body: String,
+}
+
+impl StoredResponse {
+    pub(crate) fn new(fingerprint: &str, status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Self {
+        let headers = headers
+            .iter()
+            .filter(|(name, _)| {
+                !matches!(*name, &header::CONTENT_LENGTH | &header::TRANSFER_ENCODING | &header::CONNECTION | &header::DATE)
+            })
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        Self { fingerprint: fingerprint.to_string(), status: status.as_u16(), headers, body: STANDARD.encode(body) }
+    }
+
+    pub(crate) fn into_response(self) -> Response {
+        let body = STANDARD.decode(&self.body).unwrap_or_default();
+        let mut response = Response::new(Body::from(body));
+        *response.status_mut() = StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK);
+        let headers = response.headers_mut();
+        for (name, value) in self.headers {
+            if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+                headers.append(name, value);
+            }
+        }
+        headers.insert("idempotent-replayed", HeaderValue::from_static("true"));
+        response
+    }
+}
+
+enum Store {
+    Pg(&'static Pool<Postgres>),
+    Redis(Box<RedisBackend>),
+}
+
+static STORE: OnceLock<Store> = OnceLock::new();
+static LAST_CLEANUP: OnceLock<Mutex<Option<Instant>>> = OnceLock::new();
+
+const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS rmod_idempotency (
+    key text PRIMARY KEY,
+    fingerprint text NOT NULL,
+    response jsonb NOT NULL,
+    expires_at timestamptz NOT NULL
+)";
+
+pub(crate) async fn initialize_idempotency_db(db_key: &str) -> Result<(), String> {
+    if !crate::store::is_db_exists(db_key) {
+        return Err(format!("db '{}' is not set up, call config::db_setup first", db_key));
+    }
+    let pool = crate::store::db_on(db_key);
+    sqlx::query(CREATE_TABLE).execute(pool).await.map_err(|e| e.to_string())?;
+    STORE.set(Store::Pg(pool)).map_err(|_| "Idempotency store already initialized".to_string())
+}
+
+pub(crate) async fn initialize_idempotency_redis(config: &RedisLockConfig) -> Result<(), String> {
+    let backend = RedisBackend::connect(config).await?;
+    STORE.set(Store::Redis(Box::new(backend))).map_err(|_| "Idempotency store already initialized".to_string())
+}
+
+fn redis_key(store_key: &str) -> String {
+    let service_name = clog::get_config().map(|c| c.service_name.as_str()).unwrap_or_default();
+    format!("rmod:idempotency:{}:{}", service_name, store_key)
+}
+
+async fn load(store_key: &str) -> Result<Option<StoredResponse>, String> {
+    let raw: Option<String> = match STORE.get().ok_or("idempotency store not initialized")? {
+        Store::Pg(pool) => sqlx::query_scalar("SELECT response::text FROM rmod_idempotency WHERE key = $1 AND expires_at > now()")
+            .bind(store_key)
+            .fetch_optional(*pool)
+            .await
+            .map_err(|e| e.to_string())?,
+        Store::Redis(redis) => {
+            let mut conn = redis.conn().await?;
+            let res: redis::RedisResult<Option<String>> = redis::cmd("GET").arg(redis_key(store_key)).query_async(&mut conn).await;
+            match res {
+                Ok(v) => v,
+                Err(e) => {
+                    redis.reset().await;
+                    return Err(e.to_string());
+                }
+            }
+        }
+    };
+    raw.map(|s| serde_json::from_str(&s).map_err(|e| e.to_string())).transpose()
+}
+
+async fn save(store_key: &str, stored: &StoredResponse, ttl: Duration) -> Result<(), String> {
+    let json = serde_json::to_string(stored).map_err(|e| e.to_string())?;
+    match STORE.get().ok_or("idempotency store not initialized")? {
+        Store::Pg(pool) => {
+            sqlx::query(
+                "INSERT INTO rmod_idempotency (key, fingerprint, response, expires_at)
+                 VALUES ($1, $2, $3::jsonb, now() + make_interval(secs => $4))
+                 ON CONFLICT (key) DO UPDATE SET fingerprint = $2, response = $3::jsonb, expires_at = now() + make_interval(secs => $4)",
+            )
+            .bind(store_key)
+            .bind(&stored.fingerprint)
+            .bind(json)
+            .bind(ttl.as_secs_f64())
+            .execute(*pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            cleanup_expired(pool);
+            Ok(())
+        }
+        Store::Redis(redis) => {
+            let mut conn = redis.conn().await?;
+            let res: redis::RedisResult<()> =
+                redis::cmd("SET").arg(redis_key(store_key)).arg(json).arg("PX").arg(ttl.as_millis() as u64).query_async(&mut conn).await;
+            if let Err(e) = res {
+                redis.reset().await;
+                return Err(e.to_string());
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Redis expires the keys itself; in PostgreSQL the expired rows are deleted at most once an hour per replica.
+fn cleanup_expired(pool: &'static Pool<Postgres>) {
+    let mut last = LAST_CLEANUP.get_or_init(|| Mutex::new(None)).lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if last.is_some_and(|t| t.elapsed() < EXPIRED_CLEANUP_INTERVAL) {
+        return;
+    }
+    *last = Some(Instant::now());
+    tokio::spawn(async move {
+        if let Err(e) = sqlx::query("DELETE FROM rmod_idempotency WHERE expires_at <= now()").execute(pool).await {
+            tracing::warn!("idempotency cleanup failed: {}", e);
+        }
+    });
+}
@@ -0,0 +1,90 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::{BoxFuture, FuseError, FuseRContext, FuseResult};
+use crate::config::JwtConfig;
+use crate::util::jwt::{self, VerifiedClaims};
+use axum::http::header;
+use std::sync::{Arc, OnceLock};
+
+const CLAIMS_KEY: &str = "jwt_claims";
+
+static JWT: OnceLock<JwtConfig> = OnceLock::new();
+
+pub(crate) fn initialize_jwt(config: &JwtConfig) -> Result<(), String> {
+    let config = JwtConfig {
+        secrets: config.secrets.clone(),
+        issuer: config.issuer.clone(),
+        audience: config.audience.clone(),
+        leeway: config.leeway,
+    };
+    JWT.set(config).map_err(|_| "JWT auth already initialized".to_string())
+}
+
+/// Token of an `Authorization: Bearer <token>` value.
+fn bearer(value: &str) -> Option<&str> {
+    let (scheme, token) = value.split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+}
+
+/// Precondition accepting requests with a bearer token valid for `config::jwt_auth`; all claims are kept for
+/// `ctx.jwt_claims()` and `sub` becomes the clog user uid. Answers 401 otherwise.
+pub fn jwt_auth(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let Some(config) = JWT.get() else {
+            return ctx.fail(FuseError::internal("authentication unavailable").with_source("JWT auth not initialized"));
+        };
+
+        let token = ctx.req.headers().get(header::AUTHORIZATION).and_then(|v| v.to_str().ok()).and_then(bearer).map(str::to_string);
+        let Some(token) = token else {
+            ctx.set_header(header::WWW_AUTHENTICATE.as_str(), "Bearer");
+            return ctx.fail(FuseError::unauthorized("missing bearer token"));
+        };
+
+        match jwt::verify(&token, config) {
+            Ok(claims) => {
+                if let Some(sub) = claims.sub() {
+                    ctx.set_user_uid(sub);
+                }
+                ctx.set(CLAIMS_KEY, claims);
+                ctx.ok(axum::http::StatusCode::OK, ())
+            }
+            Err(e) => {
+                ctx.set_header(header::WWW_AUTHENTICATE.as_str(), "Bearer error=\"invalid_token\"");
+                ctx.fail(FuseError::unauthorized("invalid bearer token").with_source(e))
+            }
+        }
+    })
+}
+
+/// gRPC counterpart of `jwt_auth`, for `InterceptedService::new(service, jwt_interceptor)` or
+/// `XServer::with_interceptor`; the `VerifiedClaims` are put in the request extensions.
+#[allow(clippy::result_large_err)]
+pub fn jwt_interceptor(mut req: tonic::Request<()>) -> Result<tonic::Request<()>, tonic::Status> {
+    let config = JWT.get().ok_or_else(|| tonic::Status::internal("authentication unavailable"))?;
+    let token = req.metadata().get("authorization").and_then(|v| v.to_str().ok()).and_then(bearer);
+    let token = token.ok_or_else(|| tonic::Status::unauthenticated("missing bearer token"))?;
+
+    let claims = jwt::verify(token, config).map_err(|_| tonic::Status::unauthenticated("invalid bearer token"))?;
+    if let Some(sub) = claims.sub() {
+        crate::clog::set_user_uid(sub);
+    }
+    req.extensions_mut().insert(claims);
+    Ok(req)
+}
+
+impl FuseRContext {
+    /// Claims of the bearer token accepted by the `jwt_auth` precondition; `parse::<jwt::Claims>()` or a custom
+    /// struct gives a typed view.
+    pub fn jwt_claims(&self) -> Option<Arc<VerifiedClaims>> {
+        self.get::<VerifiedClaims>(CLAIMS_KEY)
+    }
+}
@@ -0,0 +1,273 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::Fuse;
+use crate::util::env;
+use axum::Router;
+use axum::http::{HeaderName, HeaderValue, Method, header};
+use std::time::Duration;
+use tower_http::compression::CompressionLayer;
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, Any, CorsLayer};
+use tower_http::set_header::SetResponseHeaderLayer;
+
+/// CORS policy of a REST server. An empty method or header list mirrors what the preflight asks for,
+/// `"*"` allows any value but cannot be combined with `credentials`.
+#[derive(Clone, Default)]
+pub struct FuseCors {
+    origins: Vec<String>,
+    methods: Vec<String>,
+    headers: Vec<String>,
+    credentials: bool,
+    max_age: Option<Duration>,
+}
+
+pub fn cors() -> FuseCors {
+    FuseCors::default()
+}
+
+impl FuseCors {
+    pub fn origins(mut self, origins: &[&str]) -> Self {
+        self.origins = origins.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    pub fn methods(mut self, methods: &[&str]) -> Self {
+        self.methods = methods.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    pub fn headers(mut self, headers: &[&str]) -> Self {
+        self.headers = headers.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    pub fn credentials(mut self) -> Self {
+        self.credentials = true;
+        self
+    }
+
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Reads `RMOD_CORS_ALLOW_ORIGINS`, `RMOD_CORS_ALLOW_METHODS`, `RMOD_CORS_ALLOW_HEADERS` (comma separated),
+    /// `RMOD_CORS_ALLOW_CREDENTIALS` and `RMOD_CORS_MAX_AGE` (e.g. `10m`); `None` when no origin is configured.
+    pub fn from_env() -> Option<Self> {
+        let origins = env_list("RMOD_CORS_ALLOW_ORIGINS");
+        if origins.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            origins,
+            methods: env_list("RMOD_CORS_ALLOW_METHODS"),
+            headers: env_list("RMOD_CORS_ALLOW_HEADERS"),
+            credentials: env::bool_or("RMOD_CORS_ALLOW_CREDENTIALS", false),
+            max_age: env::string_opt("RMOD_CORS_MAX_AGE").map(|v| crate::time::to_duration(&v)),
+        })
+    }
+
+    fn build(&self) -> Result<CorsLayer, String> {
+        let wildcard = |ls: &[String]| ls.iter().any(|v| v == "*");
+        if self.credentials && (wildcard(&self.origins) || wildcard(&self.methods) || wildcard(&self.headers)) {
+            return Err("cors: \"*\" cannot be used together with credentials".to_string());
+        }
+
+        let origin = if wildcard(&self.origins) {
+            AllowOrigin::from(Any)
+        } else {
+            let mut origins = Vec::new();
+            for v in &self.origins {
+                origins.push(HeaderValue::from_str(v).map_err(|_| format!("cors: invalid origin '{}'", v))?);
+            }
+            AllowOrigin::list(origins)
+        };
+
+        let methods = if self.methods.is_empty() {
+            AllowMethods::mirror_request()
+        } else if wildcard(&self.methods) {
+            AllowMethods::from(Any)
+        } else {
+            let mut methods = Vec::new();
+            for v in &self.methods {
+                methods.push(Method::from_bytes(v.to_uppercase().as_bytes()).map_err(|_| format!("cors: invalid method '{}'", v))?);
+            }
+            AllowMethods::list(methods)
+        };
+
+        let headers = if self.headers.is_empty() {
+            AllowHeaders::mirror_request()
+        } else if wildcard(&self.headers) {
+            AllowHeaders::from(Any)
+        } else {
+            let mut headers = Vec::new();
+            for v in &self.headers {
+                headers.push(HeaderName::try_from(v.as_str()).map_err(|_| format!("cors: invalid header '{}'", v))?);
+            }
+            AllowHeaders::list(headers)
+        };
+
+        let mut layer = CorsLayer::new().allow_origin(origin).allow_methods(methods).allow_headers(headers);
+        if self.credentials {
+            layer = layer.allow_credentials(true);
+        }
+        if let Some(max_age) = self.max_age {
+            layer = layer.max_age(max_age);
+        }
+        Ok(layer)
+    }
+}
+
+/// Security headers added to every response that does not set them itself.
+/// All of them are on by default: HSTS for one year including subdomains, `nosniff`, `DENY` framing.
+#[derive(Clone)]
+pub struct FuseSecurityHeaders {
+    hsts: Option<(Duration, bool)>,
+    content_type_options: bool,
+    frame_options: Option<String>,
+}
+
+impl Default for FuseSecurityHeaders {
+    fn default() -> Self {
+        Self { hsts: Some((Duration::from_secs(31_536_000), true)), content_type_options: true, frame_options: Some("DENY".to_string()) }
+    }
+}
+
+pub fn security_headers() -> FuseSecurityHeaders {
+    FuseSecurityHeaders::default()
+}
+
+impl FuseSecurityHeaders {
+    pub fn hsts(mut self, max_age: Duration, include_subdomains: bool) -> Self {
+        self.hsts = Some((max_age, include_subdomains));
+        self
+    }
+
+    pub fn no_hsts(mut self) -> Self {
+        self.hsts = None;
+        self
+    }
+
+    pub fn no_content_type_options(mut self) -> Self {
+        self.content_type_options = false;
+        self
+    }
+
+    /// Value of `X-Frame-Options`, normally `DENY` or `SAMEORIGIN`.
+    pub fn frame_options(mut self, value: &str) -> Self {
+        self.frame_options = Some(value.to_string());
+        self
+    }
+
+    pub fn no_frame_options(mut self) -> Self {
+        self.frame_options = None;
+        self
+    }
+
+    /// Reads `RMOD_SECURITY_HEADERS` (bool), `RMOD_SECURITY_HSTS_MAX_AGE` (e.g. `365d`, `0` turns HSTS off)
+    /// and `RMOD_SECURITY_FRAME_OPTIONS`; `None` unless `RMOD_SECURITY_HEADERS=true`.
+    pub fn from_env() -> Option<Self> {
+        if !env::bool_or("RMOD_SECURITY_HEADERS", false) {
+            return None;
+        }
+
+        let mut opt = Self::default();
+        if let Some(v) = env::string_opt("RMOD_SECURITY_HSTS_MAX_AGE") {
+            let max_age = crate::time::to_duration(&v);
+            opt = if max_age.is_zero() { opt.no_hsts() } else { opt.hsts(max_age, true) };
+        }
+        if let Some(v) = env::string_opt("RMOD_SECURITY_FRAME_OPTIONS") {
+            opt = opt.frame_options(&v);
+        }
+        Some(opt)
+    }
+
+    fn build(&self) -> Result<Vec<(HeaderName, HeaderValue)>, String> {
+        let mut headers = Vec::new();
+        if let Some((max_age, include_subdomains)) = self.hsts {
+            let mut value = format!("max-age={}", max_age.as_secs());
+            if include_subdomains {
+                value.push_str("; includeSubDomains");
+            }
+            headers.push((header::STRICT_TRANSPORT_SECURITY, HeaderValue::from_str(&value).map_err(|e| e.to_string())?));
+        }
+        if self.content_type_options {
+            headers.push((header::X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff")));
+        }
+        if let Some(v) = &self.frame_options {
+            let value = HeaderValue::from_str(v).map_err(|_| format!("security headers: invalid frame options '{}'", v))?;
+            headers.push((header::X_FRAME_OPTIONS, value));
+        }
+        Ok(headers)
+    }
+}
+
+/// Router-wide layers, applied once every endpoint has been registered.
+#[derive(Clone, Default)]
+pub(crate) struct FuseLayers {
+    cors: Option<CorsLayer>,
+    compression: bool,
+    security_headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+impl FuseLayers {
+    pub(crate) fn apply(&self, mut router: Router) -> Router {
+        for (name, value) in &self.security_headers {
+            router = router.layer(SetResponseHeaderLayer::if_not_present(name.clone(), value.clone()));
+        }
+        if self.compression {
+            router = router.layer(CompressionLayer::new().gzip(true).br(true));
+        }
+        if let Some(cors) = &self.cors {
+            router = router.layer(cors.clone());
+        }
+        router
+    }
+}
+
+impl Fuse {
+    pub fn cors(&mut self, opt: FuseCors) {
+        match opt.build() {
+            Ok(layer) => self.layers.cors = Some(layer),
+            Err(e) => self.errors.push(e),
+        }
+    }
+
+    /// Compresses response bodies with gzip or brotli, following the request `Accept-Encoding`.
+    pub fn compression(&mut self) {
+        self.layers.compression = true;
+    }
+
+    pub fn security_headers(&mut self, opt: FuseSecurityHeaders) {
+        match opt.build() {
+            Ok(headers) => self.layers.security_headers = headers,
+            Err(e) => self.errors.push(e),
+        }
+    }
+
+    /// Enables the layers configured through env: CORS when `RMOD_CORS_ALLOW_ORIGINS` is set,
+    /// compression when `RMOD_HTTP_COMPRESSION=true` and security headers when `RMOD_SECURITY_HEADERS=true`.
+    pub fn layers_from_env(&mut self) {
+        if let Some(opt) = FuseCors::from_env() {
+            self.cors(opt);
+        }
+        if env::bool_or("RMOD_HTTP_COMPRESSION", false) {
+            self.compression();
+        }
+        if let Some(opt) = FuseSecurityHeaders::from_env() {
+            self.security_headers(opt);
+        }
+    }
+}
+
+fn env_list(name: &str) -> Vec<String> {
+    env::string_opt(name).map(|v| v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()).unwrap_or_default()
+}
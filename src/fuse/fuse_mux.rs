@@ -0,0 +1,101 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::Fuse;
+use axum::Router;
+use axum::body::Body;
+use axum::extract::ConnectInfo;
+use axum::http::{Request, header};
+use axum::response::Response;
+use futures_util::future::BoxFuture;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::task::{Context, Poll};
+use tonic::transport::server::TcpConnectInfo;
+use tower::ServiceExt;
+
+/// Serves the fuse endpoints and a gRPC service on one listener: requests with an `application/grpc` content type
+/// go to the gRPC service, the others to fuse. Both keep the logging, health checks and rate limits of `rest` and
+/// `grpc`, share the graceful shutdown, and use TLS when it is configured.
+pub async fn rest_grpc<F, S, G>(addr: &str, f: F, service: S, on_start: Option<G>)
+where
+    F: FnOnce(&mut Fuse),
+    S: tonic::codegen::Service<
+            tonic::codegen::http::Request<tonic::body::BoxBody>,
+            Response = tonic::codegen::http::Response<tonic::body::BoxBody>,
+            Error = std::convert::Infallible,
+        > + tonic::server::NamedService
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+    G: FnOnce(),
+{
+    let mut fuse = Fuse::new();
+    f(&mut fuse);
+    fuse.exit_on_errors();
+    if let Err(e) = super::validate_grpc_limits() {
+        tracing::error!("Invalid gRPC server setup: {}", e);
+        std::process::exit(1);
+    }
+
+    crate::util::lifecycle::start();
+    let listener = super::bind(addr, "REST/gRPC").await;
+    if let Some(f) = on_start {
+        f();
+    }
+
+    let mux = FuseMux { rest: fuse.into_router(), grpc: crate::fuse::grpc_routes(service).await, local_addr: listener.local_addr().ok() };
+    super::serve(listener, mux, "REST/gRPC").await;
+    crate::util::lifecycle::wait().await;
+}
+
+#[derive(Clone)]
+pub(crate) struct FuseMux {
+    pub rest: Router,
+    pub grpc: tonic::service::Routes,
+    pub local_addr: Option<SocketAddr>,
+}
+
+fn is_grpc(req: &Request<Body>) -> bool {
+    req.headers().get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()).is_some_and(|v| v.starts_with("application/grpc"))
+}
+
+impl tower::Service<Request<Body>> for FuseMux {
+    type Response = Response;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Response, Infallible>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // the router and the routes are always ready, each request gets its own clone
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        if !is_grpc(&req) {
+            return Box::pin(self.rest.clone().oneshot(req));
+        }
+
+        // the gRPC rate limits key on the peer address tonic's own server provides
+        let (mut parts, body) = req.into_parts();
+        let remote_addr = parts.extensions.get::<ConnectInfo<SocketAddr>>().map(|ConnectInfo(addr)| *addr);
+        parts.extensions.insert(TcpConnectInfo { local_addr: self.local_addr, remote_addr });
+        let req = Request::from_parts(parts, tonic::body::boxed(body));
+
+        let grpc = self.grpc.clone();
+        Box::pin(async move {
+            let res = match grpc.oneshot(req).await {
+                Ok(res) => res,
+                Err(e) => tonic::Status::from_error(e).into_http(),
+            };
+            Ok(res.map(Body::new))
+        })
+    }
+}
@@ -0,0 +1,206 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::{FuseSchemaFn, FuseSchemaGen};
+use axum::Router;
+use axum::http::{StatusCode, header};
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+static API_DOCS_ENABLED: OnceLock<bool> = OnceLock::new();
+
+/// Whether `/openapi.json` and `/docs` are served, from `RMOD_API_DOCS` (default `true`).
+fn api_docs_enabled() -> bool {
+    *API_DOCS_ENABLED.get_or_init(|| crate::util::env::bool_or("RMOD_API_DOCS", true))
+}
+
+/// Documentation of an endpoint, attached with `opt().doc(..)`; `#[fuse_handler(summary = "...", request = T, ...)]`
+/// declares it as a `<HANDLER>_DOC` const.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FuseDoc {
+    pub summary: Option<&'static str>,
+    pub description: Option<&'static str>,
+    pub tags: &'static [&'static str],
+    pub request: Option<FuseSchemaFn>,
+    pub response: Option<FuseSchemaFn>,
+    /// Success status, `200` when not set.
+    pub status: Option<u16>,
+}
+
+pub(crate) struct FuseOperation {
+    methods: Vec<&'static str>,
+    path: String,
+    doc: FuseDoc,
+}
+
+/// Registered endpoints and the document info, turned into the OpenAPI document when the router is built.
+#[derive(Default)]
+pub(crate) struct FuseApiDocs {
+    pub title: Option<String>,
+    pub version: Option<String>,
+    operations: Vec<FuseOperation>,
+}
+
+impl FuseApiDocs {
+    pub(crate) fn add(&mut self, methods: &[(&'static str, axum::routing::MethodFilter)], path: &str, doc: FuseDoc) {
+        let methods = methods.iter().map(|(name, _)| *name).collect();
+        self.operations.push(FuseOperation { methods, path: path.to_string(), doc });
+    }
+
+    pub(crate) fn document(&self) -> Value {
+        let mut schemas = FuseSchemaGen::default();
+        let mut paths: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+        for op in &self.operations {
+            let (path, params) = openapi_path(&op.path);
+            let item = paths.entry(path).or_default();
+            for method in &op.methods {
+                // OpenAPI has no CONNECT operation, `ANY` endpoints are documented without it
+                if *method == "CONNECT" {
+                    continue;
+                }
+                item.insert(method.to_lowercase(), operation(&op.doc, &params, &mut schemas));
+            }
+        }
+
+        let title = self.title.clone().or_else(|| crate::clog::get_config().map(|c| c.service_name.clone())).filter(|t| !t.is_empty());
+        json!({
+            "openapi": "3.0.3",
+            "info": {
+                "title": title.unwrap_or_else(|| "API".to_string()),
+                "version": self.version.clone().unwrap_or_else(|| "1.0.0".to_string()),
+            },
+            "paths": paths,
+            "components": { "schemas": schemas.into_components() },
+        })
+    }
+}
+
+fn operation(doc: &FuseDoc, params: &[String], schemas: &mut FuseSchemaGen) -> Value {
+    let mut op = Map::new();
+    if let Some(summary) = doc.summary {
+        op.insert("summary".to_string(), json!(summary));
+    }
+    if let Some(description) = doc.description {
+        op.insert("description".to_string(), json!(description));
+    }
+    if !doc.tags.is_empty() {
+        op.insert("tags".to_string(), json!(doc.tags));
+    }
+    if !params.is_empty() {
+        let params: Vec<Value> =
+            params.iter().map(|name| json!({ "name": name, "in": "path", "required": true, "schema": { "type": "string" } })).collect();
+        op.insert("parameters".to_string(), json!(params));
+    }
+    if let Some(request) = doc.request {
+        let schema = request(schemas);
+        op.insert("requestBody".to_string(), json!({ "required": true, "content": { "application/json": { "schema": schema } } }));
+    }
+
+    let status = doc.status.and_then(|s| StatusCode::from_u16(s).ok()).unwrap_or(StatusCode::OK);
+    let mut response = json!({ "description": status.canonical_reason().unwrap_or("Success") });
+    if let Some(res) = doc.response {
+        response["content"] = json!({ "application/json": { "schema": res(schemas) } });
+    }
+    op.insert("responses".to_string(), json!({ status.as_str(): response }));
+    Value::Object(op)
+}
+
+/// axum and OpenAPI both write parameters as `{name}`, only the catch-all `{*name}` differs.
+fn openapi_path(path: &str) -> (String, Vec<String>) {
+    let mut params = Vec::new();
+    let path = path
+        .split('/')
+        .map(|seg| match seg.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(name) => {
+                let name = name.trim_start_matches('*');
+                params.push(name.to_string());
+                format!("{{{}}}", name)
+            }
+            None => seg.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    (path, params)
+}
+
+/// Adds `/openapi.json` and the `/docs` page unless the service registered the path itself. Like the health
+/// routes they are plain routes outside the fuse handler chain.
+pub(crate) fn mount(mut router: Router, shapes: &HashMap<String, String>, docs: &FuseApiDocs) -> Router {
+    if !api_docs_enabled() {
+        return router;
+    }
+    if !shapes.contains_key("/openapi.json") {
+        let document = axum::body::Bytes::from(docs.document().to_string());
+        router = router
+            .route("/openapi.json", get(move || async move { ([(header::CONTENT_TYPE, "application/json")], document).into_response() }));
+    }
+    if !shapes.contains_key("/docs") {
+        router = router.route("/docs", get(|| async { Html(DOCS_PAGE) }));
+    }
+    router
+}
+
+/// Renders `openapi.json` without any external script, so it also works where the CDNs are unreachable.
+const DOCS_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>API docs</title>
+<style>
+body { font-family: sans-serif; margin: 2rem auto; max-width: 960px; color: #222; }
+details { border: 1px solid #ddd; border-radius: 4px; margin: .5rem 0; padding: .5rem; }
+summary { cursor: pointer; }
+.method { display: inline-block; min-width: 4.5rem; font-weight: bold; text-transform: uppercase; }
+.path { font-family: monospace; }
+pre { background: #f6f6f6; padding: .5rem; overflow-x: auto; }
+</style>
+</head>
+<body>
+<h1 id="title">API docs</h1>
+<div id="operations"></div>
+<h2>Schemas</h2>
+<div id="schemas"></div>
+<script>
+const el = (tag, text) => { const e = document.createElement(tag); if (text !== undefined) e.textContent = text; return e; };
+const section = (parent, label, value) => { parent.appendChild(el("h4", label)); parent.appendChild(el("pre", JSON.stringify(value, null, 2))); };
+fetch("openapi.json").then(r => r.json()).then(api => {
+  document.getElementById("title").textContent = api.info.title + " " + api.info.version;
+  const ops = document.getElementById("operations");
+  for (const [path, item] of Object.entries(api.paths)) {
+    for (const [method, op] of Object.entries(item)) {
+      const d = el("details"), s = el("summary");
+      s.appendChild(el("span", method)).className = "method";
+      s.appendChild(el("span", path)).className = "path";
+      if (op.summary) s.appendChild(el("span", " - " + op.summary));
+      d.appendChild(s);
+      if (op.description) d.appendChild(el("p", op.description));
+      if (op.parameters) section(d, "Parameters", op.parameters);
+      if (op.requestBody) section(d, "Request", op.requestBody.content["application/json"].schema);
+      for (const [status, res] of Object.entries(op.responses)) {
+        section(d, "Response " + status, res.content ? res.content["application/json"].schema : res.description);
+      }
+      ops.appendChild(d);
+    }
+  }
+  const schemas = document.getElementById("schemas");
+  for (const [name, schema] of Object.entries(api.components.schemas)) {
+    const d = el("details");
+    d.appendChild(el("summary", name));
+    d.appendChild(el("pre", JSON.stringify(schema, null, 2)));
+    schemas.appendChild(d);
+  }
+});
+</script>
+</body>
+</html>
+"#;
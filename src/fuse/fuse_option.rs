@@ -0,0 +1,60 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+/// Per-endpoint behaviour, registered with `Fuse::option` before the endpoint key is passed to `Fuse::endpoints`.
+#[derive(Clone, Default)]
+pub struct FuseOptions {
+    pub(crate) stream: bool,
+    pub(crate) ws: Option<super::FuseWsHandler>,
+    pub(crate) rate_limit: Option<super::FuseRateLimit>,
+    pub(crate) timeout: Option<std::time::Duration>,
+    pub(crate) idempotency: Option<super::FuseIdempotency>,
+    pub(crate) doc: Option<super::FuseDoc>,
+}
+
+pub fn opt() -> FuseOptions {
+    FuseOptions::default()
+}
+
+impl FuseOptions {
+    /// Hands the request body to the handler as a stream instead of buffering it,
+    /// and passes the response body through without buffering it for clog.
+    pub fn stream(mut self) -> Self {
+        self.stream = true;
+        self
+    }
+
+    /// Rejects requests over `limit` with 429 and `Retry-After` before the preconditions run.
+    pub fn rate_limit(mut self, limit: super::FuseRateLimit) -> Self {
+        self.rate_limit = Some(limit);
+        self
+    }
+
+    /// Overrides `RMOD_HANDLER_TIMEOUT` for this endpoint; `Duration::ZERO` disables the timeout.
+    /// On timeout the chain is dropped, defer runs with a 504 and the response is 504 unless defer changes it.
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Replays the stored response for a repeated `Idempotency-Key`, after the preconditions and before the
+    /// handlers; keys are scoped to the partner and user uid the preconditions set, a different request under the
+    /// same key gets 422.
+    pub fn idempotency(mut self, idempotency: super::FuseIdempotency) -> Self {
+        self.idempotency = Some(idempotency);
+        self
+    }
+
+    /// Documents the endpoint in `/openapi.json`, usually with the `<HANDLER>_DOC` const of `#[fuse_handler(...)]`.
+    pub fn doc(mut self, doc: super::FuseDoc) -> Self {
+        self.doc = Some(doc);
+        self
+    }
+}
@@ -0,0 +1,61 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use futures_util::FutureExt;
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::panic::AssertUnwindSafe;
+use std::sync::Once;
+
+/// A panic caught in a fuse handler stage.
+pub(crate) struct FusePanic {
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: Option<Backtrace>,
+}
+
+thread_local! {
+    static LAST_PANIC: RefCell<Option<(String, Backtrace)>> = const { RefCell::new(None) };
+}
+
+static HOOK: Once = Once::new();
+
+/// The payload of a panic has neither location nor backtrace, so a hook records them on the panicking
+/// thread; `catch` takes them right after the unwind, still on that thread. The previous hook keeps running.
+fn install_hook() {
+    HOOK.call_once(|| {
+        let prev = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let location = info.location().map(|l| format!("{}:{}", l.file(), l.line())).unwrap_or_default();
+            LAST_PANIC.with(|p| *p.borrow_mut() = Some((location, Backtrace::force_capture())));
+            prev(info);
+        }));
+    });
+}
+
+/// Polls `fut` and turns a panic inside it into `Err`.
+pub(crate) async fn catch<F: Future>(fut: F) -> Result<F::Output, FusePanic> {
+    install_hook();
+    AssertUnwindSafe(fut).catch_unwind().await.map_err(|payload| {
+        let (location, backtrace) = LAST_PANIC.with(|p| p.borrow_mut().take()).unzip();
+        FusePanic { message: panic_message(payload.as_ref()), location, backtrace }
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&'static str>() {
+        return s.to_string();
+    }
+    if let Some(s) = payload.downcast_ref::<String>() {
+        return s.clone();
+    }
+    "unknown panic".to_string()
+}
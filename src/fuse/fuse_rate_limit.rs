@@ -0,0 +1,286 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::{FuseError, FuseRContext};
+use crate::clog;
+use crate::config::RedisLockConfig;
+use axum::extract::ConnectInfo;
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::Response;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+/// Token bucket: `capacity` requests, refilled evenly over `per`. Buckets are kept per endpoint and per client,
+/// in memory unless a Redis backend was set up with `config::rate_limit_redis`.
+#[derive(Clone, Debug)]
+pub struct FuseRateLimit {
+    capacity: u32,
+    per: Duration,
+    key: RateLimitKey,
+}
+
+#[derive(Clone, Debug)]
+enum RateLimitKey {
+    ClientIp,
+    Header(&'static str),
+    Partner,
+}
+
+/// Allows `capacity` requests per `per` for each client, keyed by `client_ip()` unless set otherwise.
+pub fn rate_limit(capacity: u32, per: Duration) -> FuseRateLimit {
+    FuseRateLimit { capacity, per, key: RateLimitKey::ClientIp }
+}
+
+impl FuseRateLimit {
+    pub fn by_client_ip(mut self) -> Self {
+        self.key = RateLimitKey::ClientIp;
+        self
+    }
+
+    /// Keys the buckets by a request header, e.g. an api key; requests without it fall back to the client ip.
+    pub fn by_header(mut self, name: &'static str) -> Self {
+        self.key = RateLimitKey::Header(name);
+        self
+    }
+
+    /// Keys the buckets by the partner uid of the clog context (`x-partner-uid`, or `set_partner_uid`
+    /// in an earlier layer); requests without one fall back to the client ip.
+    pub fn by_partner(mut self) -> Self {
+        self.key = RateLimitKey::Partner;
+        self
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.capacity == 0 {
+            return Err("rate limit capacity must be greater than 0".to_string());
+        }
+        if self.per.is_zero() {
+            return Err("rate limit period must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+
+    fn refill_per_sec(&self) -> f64 {
+        self.capacity as f64 / self.per.as_secs_f64()
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Limiter of one endpoint key (REST) or method path (gRPC).
+pub(crate) struct RateLimiter {
+    name: &'static str,
+    limit: FuseRateLimit,
+    buckets: moka::sync::Cache<String, Arc<Mutex<Bucket>>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(name: &'static str, limit: FuseRateLimit) -> Self {
+        // an idle bucket is full again after `per`, so dropping it then changes nothing
+        let buckets = moka::sync::Cache::builder().max_capacity(100_000).time_to_idle(limit.per).build();
+        Self { name, limit, buckets }
+    }
+
+    /// Takes a token for the client of the request; returns how long to wait when the bucket is empty.
+    /// Must run inside the clog context of the request.
+    pub(crate) async fn check(&self, headers: &HeaderMap, remote: Option<IpAddr>) -> Option<Duration> {
+        let (key_type, key) = self.client_key(headers, remote);
+        let bucket_key = format!("{}:{}", key_type, key);
+
+        let retry_after = match REDIS.get() {
+            Some(redis) => match self.take_redis(redis, &bucket_key).await {
+                Ok(v) => v,
+                Err(e) => {
+                    // fail open: an unreachable Redis must not take the endpoints down with it
+                    tracing::warn!("rate limit check failed for '{}': {}", self.name, e);
+                    None
+                }
+            },
+            None => self.take_memory(&bucket_key),
+        }?;
+
+        let payload = serde_json::json!({
+            "endpoint": self.name,
+            "key_type": key_type,
+            "key": key,
+            "capacity": self.limit.capacity,
+            "per_ms": self.limit.per.as_millis() as u64,
+            "retry_after_ms": retry_after.as_millis() as u64,
+        });
+        let status_code = StatusCode::TOO_MANY_REQUESTS.as_u16() as i32;
+        if let Some(entry) = clog::new_log_entry("RATE_LIMITED", self.name, 0, status_code, payload.to_string()) {
+            clog::push_log(entry);
+        }
+        Some(retry_after)
+    }
+
+    /// REST side of `check`: `Some(429 response)` when the request is over the limit.
+    /// Only the headers are borrowed across the await, the request body is not `Sync`.
+    pub(crate) fn check_request<'a>(&'a self, ctx: &'a FuseRContext) -> impl Future<Output = Option<Response>> + Send + 'a {
+        let remote = ctx.req.extensions().get::<ConnectInfo<SocketAddr>>().map(|ConnectInfo(addr)| addr.ip());
+        let headers = ctx.req.headers();
+        async move {
+            let retry_after = self.check(headers, remote).await?;
+            Some(too_many_requests(retry_after))
+        }
+    }
+
+    fn client_key(&self, headers: &HeaderMap, remote: Option<IpAddr>) -> (&'static str, String) {
+        let key = match &self.limit.key {
+            RateLimitKey::ClientIp => None,
+            RateLimitKey::Header(name) => {
+                headers.get(*name).and_then(|v| v.to_str().ok()).filter(|v| !v.is_empty()).map(|v| ("header", v.to_string()))
+            }
+            RateLimitKey::Partner => clog::get_current_ctx().and_then(|c| c.partner_uid).filter(|v| !v.is_empty()).map(|v| ("partner", v)),
+        };
+        key.unwrap_or_else(|| ("client_ip", super::r_context_client_ip::client_ip(headers, remote)))
+    }
+
+    fn take_memory(&self, bucket_key: &str) -> Option<Duration> {
+        let capacity = self.limit.capacity as f64;
+        let rate = self.limit.refill_per_sec();
+        let now = Instant::now();
+
+        let bucket = self.buckets.get_with_by_ref(bucket_key, || Arc::new(Mutex::new(Bucket { tokens: capacity, updated: now })));
+        let mut bucket = bucket.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        bucket.tokens = (bucket.tokens + now.saturating_duration_since(bucket.updated).as_secs_f64() * rate).min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+
+    async fn take_redis(&self, redis: &RedisBackend, bucket_key: &str) -> Result<Option<Duration>, String> {
+        let service_name = clog::get_config().map(|c| c.service_name.as_str()).unwrap_or_default();
+        let key = format!("rmod:rate_limit:{}:{}:{}", service_name, self.name, bucket_key);
+
+        let mut conn = redis.conn().await?;
+        let result: redis::RedisResult<i64> = redis::Script::new(TOKEN_BUCKET_SCRIPT)
+            .key(key)
+            .arg(self.limit.capacity)
+            .arg(self.limit.per.as_millis() as u64)
+            .invoke_async(&mut conn)
+            .await;
+
+        match result {
+            Ok(0) => Ok(None),
+            Ok(wait_ms) => Ok(Some(Duration::from_millis(wait_ms as u64))),
+            Err(e) => {
+                redis.reset().await;
+                Err(e.to_string())
+            }
+        }
+    }
+}
+
+/// Same bucket as the in-memory one, kept in a hash; returns the milliseconds to wait, 0 when a token was taken.
+const TOKEN_BUCKET_SCRIPT: &str = r#"
+local capacity = tonumber(ARGV[1])
+local per_ms = tonumber(ARGV[2])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local bucket = redis.call('HMGET', KEYS[1], 'tokens', 'updated')
+local tokens = tonumber(bucket[1]) or capacity
+local updated = tonumber(bucket[2]) or now
+tokens = math.min(capacity, tokens + math.max(0, now - updated) * capacity / per_ms)
+local wait_ms = 0
+if tokens >= 1 then
+    tokens = tokens - 1
+else
+    wait_ms = math.ceil((1 - tokens) * per_ms / capacity)
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'updated', now)
+redis.call('PEXPIRE', KEYS[1], per_ms)
+return wait_ms
+"#;
+
+/// Redis client keeping one multiplexed connection, reconnecting after `reset`; shared with the idempotency store.
+pub(crate) struct RedisBackend {
+    client: redis::Client,
+    conn: tokio::sync::Mutex<Option<redis::aio::MultiplexedConnection>>,
+}
+
+impl RedisBackend {
+    pub(crate) async fn connect(config: &RedisLockConfig) -> Result<Self, String> {
+        let client = redis::Client::open(crate::lock::redis_url(config)).map_err(|e| e.to_string())?;
+        let conn = client.get_multiplexed_async_connection().await.map_err(|e| e.to_string())?;
+        Ok(Self { client, conn: tokio::sync::Mutex::new(Some(conn)) })
+    }
+
+    pub(crate) async fn conn(&self) -> Result<redis::aio::MultiplexedConnection, String> {
+        let mut conn = self.conn.lock().await;
+        if let Some(c) = conn.as_ref() {
+            return Ok(c.clone());
+        }
+        let c = self.client.get_multiplexed_async_connection().await.map_err(|e| e.to_string())?;
+        *conn = Some(c.clone());
+        Ok(c)
+    }
+
+    pub(crate) async fn reset(&self) {
+        *self.conn.lock().await = None;
+    }
+}
+
+static REDIS: OnceLock<RedisBackend> = OnceLock::new();
+
+pub(crate) async fn initialize_rate_limit_redis(config: &RedisLockConfig) -> Result<(), String> {
+    let backend = RedisBackend::connect(config).await?;
+    REDIS.set(backend).map_err(|_| "Rate limit redis client already initialized".to_string())
+}
+
+/// 429 with `Retry-After` in whole seconds, rounded up.
+fn too_many_requests(retry_after: Duration) -> Response {
+    let mut response = FuseError::new(StatusCode::TOO_MANY_REQUESTS, "too_many_requests", "rate limit exceeded").to_response();
+    response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs(retry_after)));
+    response
+}
+
+pub(crate) fn retry_after_secs(retry_after: Duration) -> u64 {
+    retry_after.as_secs_f64().ceil().max(1.0) as u64
+}
+
+static GRPC_LIMITERS: OnceLock<RwLock<HashMap<&'static str, Arc<RateLimiter>>>> = OnceLock::new();
+
+fn get_grpc_limiters() -> &'static RwLock<HashMap<&'static str, Arc<RateLimiter>>> {
+    GRPC_LIMITERS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Rate limits a gRPC method, e.g. `"/partner.v1.PartnerService/GetPrice"`; must be called before `fuse::grpc`.
+/// Rejected calls get `RESOURCE_EXHAUSTED` with a `retry-after` header.
+pub fn add_grpc_rate_limit(method_path: &'static str, limit: FuseRateLimit) {
+    let mut limiters = get_grpc_limiters().write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    limiters.insert(method_path, Arc::new(RateLimiter::new(method_path, limit)));
+}
+
+pub(crate) fn grpc_limiter(method_path: &str) -> Option<Arc<RateLimiter>> {
+    let limiters = GRPC_LIMITERS.get()?.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+    limiters.get(method_path).cloned()
+}
+
+pub(crate) fn validate_grpc_limits() -> Result<(), String> {
+    let Some(limiters) = GRPC_LIMITERS.get() else {
+        return Ok(());
+    };
+    let limiters = limiters.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+    for (path, limiter) in limiters.iter() {
+        limiter.limit.validate().map_err(|e| format!("gRPC method '{}': {}", path, e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,210 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use crate::FCT;
+use rust_decimal::Decimal;
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+pub use rmod_macros::FuseSchema;
+
+pub type FuseSchemaFn = fn(&mut FuseSchemaGen) -> Value;
+
+/// JSON schema of a request or response body in the OpenAPI document, usually `#[derive(FuseSchema)]`.
+pub trait FuseSchema {
+    fn schema(schemas: &mut FuseSchemaGen) -> Value;
+
+    /// Whether a struct field of this type may be absent, true for `Option`.
+    fn is_optional() -> bool {
+        false
+    }
+}
+
+/// Collects the named schemas (`components.schemas`) referenced while building the document.
+#[derive(Default)]
+pub struct FuseSchemaGen {
+    components: BTreeMap<String, Value>,
+}
+
+impl FuseSchemaGen {
+    /// Reference to the component `name`, built on first use. Types are keyed by their name only, so two types
+    /// with the same name in different modules share the first one's schema.
+    pub fn component(&mut self, name: &'static str, build: impl FnOnce(&mut Self) -> Value) -> Value {
+        if !self.components.contains_key(name) {
+            // placeholder first, so a recursive type refers to itself instead of building forever
+            self.components.insert(name.to_string(), Value::Null);
+            let schema = build(self);
+            self.components.insert(name.to_string(), schema);
+        }
+        json!({ "$ref": format!("#/components/schemas/{}", name) })
+    }
+
+    pub fn string_enum(description: Option<&str>, values: &[&str]) -> Value {
+        let mut schema = json!({ "type": "string", "enum": values });
+        if let Some(description) = description {
+            schema["description"] = json!(description);
+        }
+        schema
+    }
+
+    pub(crate) fn into_components(self) -> BTreeMap<String, Value> {
+        self.components
+    }
+}
+
+/// Object schema built by the derive, one `field` per struct field.
+pub struct FuseSchemaObject {
+    description: Option<&'static str>,
+    properties: Map<String, Value>,
+    required: Vec<String>,
+}
+
+impl FuseSchemaObject {
+    pub fn new(description: Option<&'static str>) -> Self {
+        Self { description, properties: Map::new(), required: Vec::new() }
+    }
+
+    pub fn field<T: FuseSchema + ?Sized>(&mut self, schemas: &mut FuseSchemaGen, name: &str, optional: bool, description: Option<&str>) {
+        let mut schema = T::schema(schemas);
+        if let Some(description) = description {
+            // OpenAPI 3.0 ignores the siblings of `$ref`
+            if schema.get("$ref").is_some() {
+                schema = json!({ "allOf": [schema] });
+            }
+            schema["description"] = json!(description);
+        }
+        self.properties.insert(name.to_string(), schema);
+        if !optional && !T::is_optional() {
+            self.required.push(name.to_string());
+        }
+    }
+
+    pub fn build(self) -> Value {
+        let mut schema = json!({ "type": "object", "properties": self.properties });
+        if !self.required.is_empty() {
+            schema["required"] = json!(self.required);
+        }
+        if let Some(description) = self.description {
+            schema["description"] = json!(description);
+        }
+        schema
+    }
+}
+
+macro_rules! impl_schema {
+    ($($t:ty),* => $schema:tt) => {
+        $(impl FuseSchema for $t {
+            fn schema(_: &mut FuseSchemaGen) -> Value {
+                json!($schema)
+            }
+        })*
+    };
+}
+
+impl_schema!(bool => { "type": "boolean" });
+impl_schema!(i8, i16, i32 => { "type": "integer", "format": "int32" });
+impl_schema!(u8, u16, u32 => { "type": "integer", "format": "int32", "minimum": 0 });
+impl_schema!(i64, i128, isize => { "type": "integer", "format": "int64" });
+impl_schema!(u64, u128, usize => { "type": "integer", "format": "int64", "minimum": 0 });
+impl_schema!(f32 => { "type": "number", "format": "float" });
+impl_schema!(f64 => { "type": "number", "format": "double" });
+impl_schema!(str, String, char => { "type": "string" });
+impl_schema!(Decimal, FCT => { "type": "string", "format": "decimal" });
+impl_schema!(chrono::NaiveDate => { "type": "string", "format": "date" });
+impl_schema!(chrono::NaiveTime => { "type": "string", "format": "time" });
+impl_schema!(chrono::NaiveDateTime => { "type": "string", "example": "2026-01-31T13:45:00" });
+impl_schema!(Value => {});
+
+impl<Tz: chrono::TimeZone> FuseSchema for chrono::DateTime<Tz> {
+    fn schema(_: &mut FuseSchemaGen) -> Value {
+        json!({ "type": "string", "format": "date-time" })
+    }
+}
+
+impl<T: FuseSchema> FuseSchema for Option<T> {
+    fn schema(schemas: &mut FuseSchemaGen) -> Value {
+        let mut schema = T::schema(schemas);
+        if schema.get("$ref").is_some() {
+            schema = json!({ "allOf": [schema] });
+        }
+        schema["nullable"] = json!(true);
+        schema
+    }
+
+    fn is_optional() -> bool {
+        true
+    }
+}
+
+macro_rules! impl_schema_array {
+    ($($t:ident),* => $unique:expr) => {
+        $(impl<T: FuseSchema> FuseSchema for $t<T> {
+            fn schema(schemas: &mut FuseSchemaGen) -> Value {
+                let mut schema = json!({ "type": "array", "items": T::schema(schemas) });
+                if $unique {
+                    schema["uniqueItems"] = json!(true);
+                }
+                schema
+            }
+        })*
+    };
+}
+
+impl_schema_array!(Vec, VecDeque => false);
+impl_schema_array!(HashSet, BTreeSet => true);
+
+impl<T: FuseSchema> FuseSchema for [T] {
+    fn schema(schemas: &mut FuseSchemaGen) -> Value {
+        Vec::<T>::schema(schemas)
+    }
+}
+
+impl<K, V: FuseSchema> FuseSchema for HashMap<K, V> {
+    fn schema(schemas: &mut FuseSchemaGen) -> Value {
+        json!({ "type": "object", "additionalProperties": V::schema(schemas) })
+    }
+}
+
+impl<K, V: FuseSchema> FuseSchema for BTreeMap<K, V> {
+    fn schema(schemas: &mut FuseSchemaGen) -> Value {
+        HashMap::<K, V>::schema(schemas)
+    }
+}
+
+impl<T: FuseSchema + ?Sized> FuseSchema for Box<T> {
+    fn schema(schemas: &mut FuseSchemaGen) -> Value {
+        T::schema(schemas)
+    }
+
+    fn is_optional() -> bool {
+        T::is_optional()
+    }
+}
+
+impl<T: FuseSchema + ?Sized> FuseSchema for Arc<T> {
+    fn schema(schemas: &mut FuseSchemaGen) -> Value {
+        T::schema(schemas)
+    }
+
+    fn is_optional() -> bool {
+        T::is_optional()
+    }
+}
+
+impl<T: FuseSchema + ?Sized> FuseSchema for &T {
+    fn schema(schemas: &mut FuseSchemaGen) -> Value {
+        T::schema(schemas)
+    }
+
+    fn is_optional() -> bool {
+        T::is_optional()
+    }
+}
@@ -0,0 +1,145 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::{ANY_METHODS, EndpointMethods, Fuse, parse_method};
+use axum::body::Body;
+use axum::http::{HeaderValue, Method, Request, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::any;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tower::ServiceExt;
+use tower_http::services::{ServeDir, ServeFile};
+
+static STATIC_MAX_AGE: OnceLock<u64> = OnceLock::new();
+
+/// `max-age` of static assets other than html, from `RMOD_STATIC_MAX_AGE` (e.g. `1h`, the default).
+fn static_max_age() -> u64 {
+    *STATIC_MAX_AGE.get_or_init(|| crate::time::to_duration(&crate::util::env::string_or("RMOD_STATIC_MAX_AGE", "1h")).as_secs())
+}
+
+impl Fuse {
+    /// Serves the files under `dir` at `prefix`. Static files are served outside the fuse handler chain,
+    /// so they never reach clog and don't run any precondition.
+    pub fn static_dir(&mut self, prefix: &str, dir: &str) {
+        let prefix = super::fuse_group::normalize_prefix(prefix);
+        let key: &'static str = Box::leak(format!("STATIC: {}", if prefix.is_empty() { "/" } else { &prefix }).into_boxed_str());
+        let dir = PathBuf::from(dir);
+        let handler = move |req: Request<Body>| serve_file(dir.clone(), None, req);
+
+        if prefix.is_empty() {
+            if let Err(e) = self.claim_fallback(key) {
+                self.errors.push(e);
+                return;
+            }
+            let router = std::mem::take(&mut self.router);
+            self.router = router.fallback(handler);
+            return;
+        }
+
+        let methods: EndpointMethods = ANY_METHODS.iter().filter_map(|m| parse_method(m)).collect();
+        for path in [prefix.clone(), format!("{}/{{*path}}", prefix)] {
+            if let Err(e) = self.claim_route(key, &methods, &path) {
+                self.errors.push(e);
+                return;
+            }
+        }
+        let router = std::mem::take(&mut self.router);
+        self.router = router.nest_service(&prefix, any(handler));
+    }
+
+    /// Serves a single page app from `dir`: existing files as-is, and `index.html` for any other
+    /// GET path that is not under one of `api_prefixes` and does not look like a missing asset.
+    pub fn spa(&mut self, dir: &str, api_prefixes: &[&str]) {
+        if let Err(e) = self.claim_fallback("SPA: /") {
+            self.errors.push(e);
+            return;
+        }
+
+        let dir = PathBuf::from(dir);
+        let api_prefixes: Vec<String> =
+            api_prefixes.iter().map(|p| super::fuse_group::normalize_prefix(p)).filter(|p| !p.is_empty()).collect();
+        let handler = move |req: Request<Body>| {
+            let dir = dir.clone();
+            let path = req.uri().path().to_string();
+            let is_api = api_prefixes.iter().any(|p| path == *p || path.starts_with(&format!("{}/", p)));
+            async move {
+                if is_api || !(req.method() == Method::GET || req.method() == Method::HEAD) {
+                    return StatusCode::NOT_FOUND.into_response();
+                }
+                // "/assets/app.3f2a.js" that isn't there is a real 404, "/orders/42" is a client-side route
+                let is_asset = mime_guess::from_path(&path).first().is_some();
+                let index = if is_asset { None } else { Some(dir.join("index.html")) };
+                serve_file(dir, index, req).await
+            }
+        };
+
+        let router = std::mem::take(&mut self.router);
+        self.router = router.fallback(handler);
+    }
+
+    fn claim_fallback(&mut self, key: &'static str) -> Result<(), String> {
+        if let Some(existing) = self.routes.get("FALLBACK") {
+            return Err(format!("'{}' collides with '{}', only one of them can serve unmatched paths", key, existing));
+        }
+        self.routes.insert("FALLBACK".to_string(), key);
+        Ok(())
+    }
+}
+
+/// Serves a file from `dir` (or `index` when the file is missing), adding `Cache-Control` and a weak `ETag`.
+async fn serve_file(dir: PathBuf, index: Option<PathBuf>, req: Request<Body>) -> Response {
+    let if_none_match = req.headers().get(header::IF_NONE_MATCH).cloned();
+    let res = match index {
+        Some(index) => ServeDir::new(dir).fallback(ServeFile::new(index)).oneshot(req).await,
+        None => ServeDir::new(dir).oneshot(req).await,
+    };
+    let mut res = match res {
+        Ok(res) => res.map(Body::new),
+        Err(e) => match e {},
+    };
+    if res.status() != StatusCode::OK {
+        return res;
+    }
+
+    let is_html = res.headers().get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()).is_some_and(|v| v.starts_with("text/html"));
+    // html must be revalidated so a deploy is picked up at once, hashed assets can be cached
+    let cache_control = if is_html { "no-cache".to_string() } else { format!("public, max-age={}", static_max_age()) };
+    if let Ok(v) = HeaderValue::from_str(&cache_control) {
+        res.headers_mut().insert(header::CACHE_CONTROL, v);
+    }
+
+    let Some(etag) = etag(&res) else {
+        return res;
+    };
+    if if_none_match.is_some_and(|v| v.to_str().is_ok_and(|v| v.split(',').any(|t| t.trim() == etag || t.trim() == "*"))) {
+        let mut not_modified = StatusCode::NOT_MODIFIED.into_response();
+        for name in [header::CACHE_CONTROL, header::LAST_MODIFIED] {
+            if let Some(v) = res.headers().get(&name) {
+                not_modified.headers_mut().insert(name, v.clone());
+            }
+        }
+        res = not_modified;
+    }
+    if let Ok(v) = HeaderValue::from_str(&etag) {
+        res.headers_mut().insert(header::ETAG, v);
+    }
+    res
+}
+
+/// Weak ETag from the size and modification time `ServeDir` reports for the file.
+fn etag(res: &Response) -> Option<String> {
+    let len = res.headers().get(header::CONTENT_LENGTH)?.to_str().ok()?;
+    let modified = res.headers().get(header::LAST_MODIFIED)?.as_bytes();
+    let mut hasher = DefaultHasher::new();
+    modified.hash(&mut hasher);
+    Some(format!("W/\"{}-{:x}\"", len, hasher.finish()))
+}
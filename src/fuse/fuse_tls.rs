@@ -0,0 +1,266 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use crate::config::TlsConfig;
+use axum::serve::Listener;
+use rustls::RootCertStore;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::{ServerConfig, WebPkiClientVerifier};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, SystemTime};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::server::TlsStream;
+
+/// Time a client has to complete the TLS handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+static TLS: OnceLock<TlsSource> = OnceLock::new();
+static RELOAD_INTERVAL: OnceLock<Duration> = OnceLock::new();
+
+/// How often the certificate files are checked for changes, from `RMOD_TLS_RELOAD_INTERVAL` (default `10s`);
+/// zero disables the reload.
+fn reload_interval() -> Duration {
+    *RELOAD_INTERVAL.get_or_init(|| crate::time::to_duration(&crate::util::env::string_or("RMOD_TLS_RELOAD_INTERVAL", "10s")))
+}
+
+#[derive(Clone, Debug)]
+enum Pem {
+    File(PathBuf),
+    Inline(String),
+}
+
+impl Pem {
+    fn new(val: &str) -> Self {
+        if val.trim_start().starts_with("-----BEGIN") { Self::Inline(val.to_string()) } else { Self::File(PathBuf::from(val)) }
+    }
+
+    fn read(&self) -> Result<Vec<u8>, String> {
+        match self {
+            Self::File(path) => std::fs::read(path).map_err(|e| format!("failed to read '{}': {}", path.display(), e)),
+            Self::Inline(pem) => Ok(pem.as_bytes().to_vec()),
+        }
+    }
+
+    fn modified(&self) -> Option<SystemTime> {
+        match self {
+            Self::File(path) => std::fs::metadata(path).and_then(|m| m.modified()).ok(),
+            Self::Inline(_) => None,
+        }
+    }
+
+    fn certs(&self) -> Result<Vec<CertificateDer<'static>>, String> {
+        let pem = self.read()?;
+        let certs =
+            rustls_pemfile::certs(&mut pem.as_slice()).collect::<Result<Vec<_>, _>>().map_err(|e| format!("invalid certificate: {}", e))?;
+        if certs.is_empty() {
+            return Err(format!("no certificate found in {}", self));
+        }
+        Ok(certs)
+    }
+
+    fn key(&self) -> Result<PrivateKeyDer<'static>, String> {
+        let pem = self.read()?;
+        rustls_pemfile::private_key(&mut pem.as_slice())
+            .map_err(|e| format!("invalid private key: {}", e))?
+            .ok_or_else(|| format!("no private key found in {}", self))
+    }
+}
+
+impl std::fmt::Display for Pem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "'{}'", path.display()),
+            Self::Inline(_) => write!(f, "inline PEM"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct TlsSource {
+    cert: Pem,
+    key: Pem,
+    client_ca: Option<Pem>,
+    client_cert_optional: bool,
+}
+
+impl TlsSource {
+    pub(crate) fn new(config: &TlsConfig) -> Self {
+        Self {
+            cert: Pem::new(&config.cert),
+            key: Pem::new(&config.key),
+            client_ca: config.client_ca.as_deref().filter(|v| !v.is_empty()).map(Pem::new),
+            client_cert_optional: config.client_cert_optional,
+        }
+    }
+
+    fn pems(&self) -> impl Iterator<Item = &Pem> {
+        [&self.cert, &self.key].into_iter().chain(self.client_ca.as_ref())
+    }
+
+    fn modified(&self) -> Vec<Option<SystemTime>> {
+        self.pems().map(Pem::modified).collect()
+    }
+
+    /// rustls config with ALPN `h2` and `http/1.1`, so HTTP/2 is used when the client offers it.
+    pub(crate) fn server_config(&self) -> Result<Arc<ServerConfig>, String> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let builder =
+            ServerConfig::builder_with_provider(provider.clone()).with_safe_default_protocol_versions().map_err(|e| e.to_string())?;
+
+        let builder = match &self.client_ca {
+            Some(ca) => {
+                let mut roots = RootCertStore::empty();
+                for cert in ca.certs()? {
+                    roots.add(cert).map_err(|e| format!("invalid client CA certificate: {}", e))?;
+                }
+                let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider);
+                let verifier = if self.client_cert_optional { verifier.allow_unauthenticated() } else { verifier };
+                builder.with_client_cert_verifier(verifier.build().map_err(|e| format!("invalid client CA: {}", e))?)
+            }
+            None => builder.with_no_client_auth(),
+        };
+
+        let mut config =
+            builder.with_single_cert(self.cert.certs()?, self.key.key()?).map_err(|e| format!("invalid certificate or key: {}", e))?;
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(Arc::new(config))
+    }
+}
+
+pub(crate) fn initialize_tls(config: &TlsConfig) -> Result<(), String> {
+    let source = TlsSource::new(config);
+    source.server_config()?;
+    TLS.set(source).map_err(|_| "REST TLS already initialized".to_string())
+}
+
+/// TLS of the REST server: `config::rest_tls`, else `RMOD_TLS_CERT` and `RMOD_TLS_KEY` with the optional
+/// `RMOD_TLS_CLIENT_CA` and `RMOD_TLS_CLIENT_CERT_OPTIONAL`; `None` serves plain HTTP.
+pub(crate) fn tls_source() -> Option<TlsSource> {
+    if let Some(source) = TLS.get() {
+        return Some(source.clone());
+    }
+
+    let cert = crate::util::env::string_opt("RMOD_TLS_CERT").filter(|v| !v.is_empty())?;
+    let config = TlsConfig {
+        cert,
+        key: crate::util::env::string_or("RMOD_TLS_KEY", ""),
+        client_ca: crate::util::env::string_opt("RMOD_TLS_CLIENT_CA"),
+        client_cert_optional: crate::util::env::bool_or("RMOD_TLS_CLIENT_CERT_OPTIONAL", false),
+    };
+    Some(TlsSource::new(&config))
+}
+
+/// Listener handing over connections once their TLS handshake is done. Handshakes run in their own tasks, so a
+/// slow client never holds up the others.
+pub(crate) struct TlsListener {
+    local_addr: SocketAddr,
+    rx: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+}
+
+impl TlsListener {
+    pub(crate) fn new(listener: TcpListener, source: TlsSource) -> Result<Self, String> {
+        let local_addr = listener.local_addr().map_err(|e| e.to_string())?;
+        let config = Arc::new(RwLock::new(source.server_config()?));
+        let (tx, rx) = mpsc::channel(128);
+
+        let interval = reload_interval();
+        if !interval.is_zero() && source.pems().any(|p| matches!(p, Pem::File(_))) {
+            tokio::spawn(reload(source, config.clone(), interval, tx.clone()));
+        }
+        tokio::spawn(accept(listener, config, tx));
+        Ok(Self { local_addr, rx })
+    }
+}
+
+impl Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.rx.recv().await {
+            Some(conn) => conn,
+            // the accept task only ends once this listener is gone
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> std::io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+async fn accept(listener: TcpListener, config: Arc<RwLock<Arc<ServerConfig>>>, tx: mpsc::Sender<(TlsStream<TcpStream>, SocketAddr)>) {
+    loop {
+        let (stream, addr) = tokio::select! {
+            res = listener.accept() => match res {
+                Ok(conn) => conn,
+                Err(e) => {
+                    // per-connection errors are normal, others (e.g. out of file descriptors) need a pause
+                    if !matches!(
+                        e.kind(),
+                        std::io::ErrorKind::ConnectionRefused | std::io::ErrorKind::ConnectionAborted | std::io::ErrorKind::ConnectionReset
+                    ) {
+                        tracing::error!("REST server accept failed: {}", e);
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                    continue;
+                }
+            },
+            _ = tx.closed() => return,
+        };
+
+        let acceptor = TlsAcceptor::from(config.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone());
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                Ok(Ok(tls)) => {
+                    let _ = tx.send((tls, addr)).await;
+                }
+                Ok(Err(e)) => tracing::debug!("TLS handshake with {} failed: {}", addr, e),
+                Err(_) => tracing::debug!("TLS handshake with {} timed out", addr),
+            }
+        });
+    }
+}
+
+/// Swaps in a new config when a certificate file changes; a broken update keeps the previous one and is retried
+/// on the next change, e.g. once the key is written after the certificate.
+async fn reload(
+    source: TlsSource,
+    config: Arc<RwLock<Arc<ServerConfig>>>,
+    interval: Duration,
+    tx: mpsc::Sender<(TlsStream<TcpStream>, SocketAddr)>,
+) {
+    let mut modified = source.modified();
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = tx.closed() => return,
+        }
+
+        let current = source.modified();
+        if current == modified {
+            continue;
+        }
+        modified = current;
+
+        match source.server_config() {
+            Ok(c) => {
+                *config.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = c;
+                tracing::info!("REST server TLS certificates reloaded");
+            }
+            Err(e) => tracing::warn!("REST server TLS reload failed, keeping the previous certificates: {}", e),
+        }
+    }
+}
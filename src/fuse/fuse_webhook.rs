@@ -0,0 +1,181 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::{FuseError, FuseRContext, FuseResult};
+use crate::config::{WebhookConfig, WebhookMessage};
+use axum::http::StatusCode;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+type HmacSha256 = Hmac<Sha256>;
+
+static WEBHOOKS: OnceLock<RwLock<HashMap<String, Arc<Webhook>>>> = OnceLock::new();
+
+struct Webhook {
+    secrets: Vec<String>,
+    signature_header: String,
+    timestamp_header: String,
+    nonce_header: Option<String>,
+    message: WebhookMessage,
+    tolerance: i64,
+    /// `cache` group of the replay keys seen within the tolerance window.
+    nonce_group: String,
+}
+
+fn webhooks() -> &'static RwLock<HashMap<String, Arc<Webhook>>> {
+    WEBHOOKS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+pub(crate) fn initialize_webhook(name: &str, config: &WebhookConfig) -> Result<(), String> {
+    let mut webhooks = webhooks().write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if webhooks.contains_key(name) {
+        return Err(format!("webhook {} already initialized", name));
+    }
+
+    // a timestamp is accepted from `tolerance` in the past to `tolerance` in the future
+    let tolerance = config.tolerance.unwrap_or(300);
+    let nonce_group = format!("rmod:webhook:{}", name);
+    crate::cache::add_group_ttl::<()>(&nonce_group, &format!("{}s", tolerance * 2), 0);
+
+    let webhook = Webhook {
+        secrets: config.secrets.clone(),
+        signature_header: config.signature_header.clone(),
+        timestamp_header: config.timestamp_header.clone(),
+        nonce_header: config.nonce_header.clone(),
+        message: config.message,
+        tolerance,
+        nonce_group,
+    };
+    webhooks.insert(name.to_string(), Arc::new(webhook));
+    Ok(())
+}
+
+/// Signature bytes of a `sha256=` prefixed or bare hex / base64 value.
+fn decode_signature(value: &str) -> Option<Vec<u8>> {
+    let value = value.trim();
+    let value = value.strip_prefix("sha256=").unwrap_or(value);
+    if value.len() == 64 && value.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return (0..64).step_by(2).map(|i| u8::from_str_radix(&value[i..i + 2], 16).ok()).collect();
+    }
+    STANDARD.decode(value).ok()
+}
+
+impl Webhook {
+    fn signed(&self, timestamp: &str, body: &[u8], signatures: &[Vec<u8>]) -> bool {
+        self.secrets.iter().any(|secret| {
+            let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+            match self.message {
+                WebhookMessage::BodyTimestamp => {
+                    mac.update(body);
+                    mac.update(timestamp.as_bytes());
+                }
+                WebhookMessage::TimestampDotBody => {
+                    mac.update(timestamp.as_bytes());
+                    mac.update(b".");
+                    mac.update(body);
+                }
+            }
+            signatures.iter().any(|signature| mac.clone().verify_slice(signature).is_ok())
+        })
+    }
+
+    /// Replay keys of an authentic request, or why it is rejected. The first covers what is signed, so no edit of
+    /// the headers gets a resent callback past it; the nonce, when configured, is checked on top of it.
+    fn verify(&self, ctx: &FuseRContext) -> Result<Vec<String>, &'static str> {
+        let headers = ctx.req.headers();
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+        let timestamp = header(&self.timestamp_header).ok_or("missing timestamp")?;
+        let signed_at: i64 = timestamp.trim().parse().map_err(|_| "invalid timestamp")?;
+        if (chrono::Utc::now().timestamp() - signed_at).abs() > self.tolerance {
+            return Err("timestamp outside tolerance");
+        }
+
+        let signature = header(&self.signature_header).ok_or("missing signature")?;
+        let signatures: Vec<Vec<u8>> = signature.split(',').filter_map(decode_signature).collect();
+        if signatures.is_empty() {
+            return Err("invalid signature encoding");
+        }
+        let body = ctx.body.as_deref().unwrap_or_default();
+        if !self.signed(timestamp, body, &signatures) {
+            return Err("signature mismatch");
+        }
+
+        let mut signed = Sha256::new();
+        signed.update(timestamp.as_bytes());
+        signed.update(b"\n");
+        signed.update(body);
+        let mut keys = vec![format!("signed:{:x}", signed.finalize())];
+        if let Some(name) = &self.nonce_header {
+            keys.push(format!("nonce:{}", header(name).ok_or("missing nonce")?));
+        }
+        Ok(keys)
+    }
+}
+
+/// Replay keys reserved by a verified delivery. They are released when the final response is not a success, so
+/// the partner's retry of a delivery the handler failed, timed out or panicked on is not rejected as a replay.
+pub(crate) struct WebhookClaim {
+    group: String,
+    keys: Vec<String>,
+}
+
+impl WebhookClaim {
+    pub(crate) async fn finish(self, status: StatusCode) {
+        if !status.is_success() {
+            release(&self.group, &self.keys).await;
+        }
+    }
+}
+
+async fn release(group: &str, keys: &[String]) {
+    for key in keys {
+        crate::cache::remove_ttl::<()>(group, key).await;
+    }
+}
+
+/// Verifies the request against the webhook registered with `config::webhook(name, ..)`: the signature over
+/// `ctx.body` with any active secret, the timestamp window and that neither the signed timestamp and body nor
+/// the nonce are seen before. The keys are reserved until the response: a non-2xx response frees them for the
+/// retry. Failures are logged to clog and answered with 401, replays with 409. Use `fuse_webhook!(name)` as
+/// precondition; stream endpoints have no `ctx.body` and always fail.
+pub async fn verify_webhook(ctx: &mut FuseRContext, name: &str) -> FuseResult {
+    let webhook = webhooks().read().unwrap_or_else(|poisoned| poisoned.into_inner()).get(name).cloned();
+    let Some(webhook) = webhook else {
+        return ctx.fail(FuseError::internal("webhook verification unavailable").with_source(format!("webhook {} not initialized", name)));
+    };
+
+    let keys = match webhook.verify(ctx) {
+        Ok(keys) => keys,
+        Err(reason) => {
+            crate::clog::warn("webhook_verification", serde_json::json!({"webhook": name, "path": ctx.req.uri().path(), "reason": reason}));
+            let e = FuseError::new(StatusCode::UNAUTHORIZED, "invalid_webhook_signature", "invalid webhook signature");
+            return ctx.fail(e.with_source(reason));
+        }
+    };
+
+    let mut reserved = Vec::with_capacity(keys.len());
+    for key in keys {
+        if !crate::cache::put_ttl_if_absent(&webhook.nonce_group, &key, ()).await {
+            // only the keys of this request are freed, the one already seen stays
+            release(&webhook.nonce_group, &reserved).await;
+            let reason = "replayed nonce";
+            crate::clog::warn("webhook_verification", serde_json::json!({"webhook": name, "path": ctx.req.uri().path(), "reason": reason}));
+            return ctx.fail(FuseError::new(StatusCode::CONFLICT, "webhook_replayed", "webhook already received").with_source(reason));
+        }
+        reserved.push(key);
+    }
+
+    ctx.webhook_claim = Some(WebhookClaim { group: webhook.nonce_group.clone(), keys: reserved });
+    ctx.ok(StatusCode::OK, ())
+}
@@ -0,0 +1,207 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::{Fuse, FuseHandler, FuseRContext, FuseResult, fuse_group, fuse_panic, parse_endpoint_key};
+use crate::clog;
+use axum::extract::ws::{WebSocket, WebSocketUpgrade};
+use axum::http::StatusCode;
+use futures_util::future::BoxFuture;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+pub use axum::extract::ws::{CloseFrame as WsCloseFrame, Message as WsMessage};
+
+/// Handler of an upgraded WebSocket connection; it owns the connection until it returns.
+pub type FuseWsHandler = fn(FuseWebSocket) -> BoxFuture<'static, ()>;
+
+#[macro_export]
+macro_rules! fuse_ws_endpoints {
+    ($($key:expr => $h:expr),* $(,)?) => {
+        {
+            let mut map = ::std::collections::HashMap::<&'static str, $crate::fuse::FuseWsHandler>::new();
+            $(map.insert($key, $h as $crate::fuse::FuseWsHandler);)*
+            map
+        }
+    };
+}
+
+/// An upgraded connection. Data messages going through `recv`/`send` are counted for the `WS_DISCONNECT` log.
+pub struct FuseWebSocket {
+    socket: WebSocket,
+    data: Arc<Mutex<HashMap<String, Arc<dyn Any + Send + Sync>>>>,
+    stats: Arc<WsStats>,
+}
+
+#[derive(Default)]
+pub(crate) struct WsStats {
+    received: AtomicU64,
+    sent: AtomicU64,
+    close_code: AtomicU64,
+}
+
+impl FuseWebSocket {
+    /// Next message from the client, `None` once the connection is closed.
+    pub async fn recv(&mut self) -> Option<Result<WsMessage, axum::Error>> {
+        let msg = self.socket.recv().await;
+        match &msg {
+            Some(Ok(WsMessage::Text(_) | WsMessage::Binary(_))) => {
+                self.stats.received.fetch_add(1, Ordering::Relaxed);
+            }
+            Some(Ok(WsMessage::Close(Some(frame)))) => {
+                self.stats.close_code.store(frame.code as u64, Ordering::Relaxed);
+            }
+            _ => {}
+        }
+        msg
+    }
+
+    pub async fn send(&mut self, msg: WsMessage) -> Result<(), axum::Error> {
+        let is_data = matches!(msg, WsMessage::Text(_) | WsMessage::Binary(_));
+        self.socket.send(msg).await?;
+        if is_data {
+            self.stats.sent.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    pub async fn send_text(&mut self, text: &str) -> Result<(), axum::Error> {
+        self.send(WsMessage::Text(text.into())).await
+    }
+
+    pub async fn close(mut self, code: u16, reason: &str) -> Result<(), axum::Error> {
+        self.stats.close_code.store(code as u64, Ordering::Relaxed);
+        self.socket.send(WsMessage::Close(Some(WsCloseFrame { code, reason: reason.into() }))).await
+    }
+
+    /// Value stored with `ctx.set` by the preconditions of the upgrade request, e.g. the authenticated user.
+    pub fn get<T: Send + Sync + 'static>(&self, key: &str) -> Option<Arc<T>> {
+        let data = self.data.lock().unwrap();
+        data.get(key)?.clone().downcast::<T>().ok()
+    }
+}
+
+impl Fuse {
+    /// Registers WebSocket endpoints. Keys must be `GET`; the upgrade request runs through `precondition`
+    /// and `defer` like any other endpoint before the connection is handed to the handler.
+    pub fn ws_endpoints(&mut self, defer: FuseHandler, precondition: Vec<FuseHandler>, mapping: HashMap<&'static str, FuseWsHandler>) {
+        self.register_ws("", defer, precondition, mapping);
+    }
+
+    pub(crate) fn register_ws(
+        &mut self,
+        prefix: &str,
+        defer: FuseHandler,
+        precondition: Vec<FuseHandler>,
+        mapping: HashMap<&'static str, FuseWsHandler>,
+    ) {
+        let mut handlers: HashMap<&'static str, Vec<FuseHandler>> = HashMap::new();
+        for (key, ws) in mapping {
+            let full_key = fuse_group::prefixed_key(prefix, key);
+            match parse_endpoint_key(full_key) {
+                Ok((methods, _)) if methods.len() == 1 && methods[0].0 == "GET" => {}
+                Ok(_) => {
+                    self.errors.push(format!("websocket endpoint '{}' must use GET only", full_key));
+                    continue;
+                }
+                Err(e) => {
+                    self.errors.push(e);
+                    continue;
+                }
+            }
+
+            self.options.entry(full_key).or_default().ws = Some(ws);
+            handlers.insert(key, vec![ws_upgrade as FuseHandler]);
+        }
+        self.register(prefix, defer, precondition, handlers);
+    }
+}
+
+/// Last handler of every WebSocket endpoint: completes the upgrade and runs the connection
+/// in the clog context of the upgrade request, so its logs share the request trace id.
+fn ws_upgrade(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let Some((endpoint_key, handler)) = ctx.ws else {
+            return ctx.err(StatusCode::NOT_FOUND, ());
+        };
+        let upgrade = match ctx.extract_parts::<WebSocketUpgrade>() {
+            Ok(v) => v,
+            Err(e) => return Err(ctx.bad_request("invalid_websocket_upgrade", e)),
+        };
+
+        let log_ctx = clog::get_current_ctx();
+        let data = ctx.data.clone();
+        let path = ctx.req.uri().path().to_string();
+        let response = upgrade.on_upgrade(move |socket| async move {
+            let stats = Arc::new(WsStats::default());
+            let ws = FuseWebSocket { socket, data, stats: stats.clone() };
+
+            let run = async move {
+                push_ws_log("WS_CONNECT", endpoint_key, 0, serde_json::json!({ "endpoint": endpoint_key, "path": path }));
+
+                let start_time = std::time::Instant::now();
+                // a panic ends only this connection, its disconnect is still logged
+                let panic = fuse_panic::catch(handler(ws)).await.err();
+
+                if let Some(panic) = &panic {
+                    tracing::error!(
+                        "websocket handler panicked: {} [endpoint: {}, at: {}]",
+                        panic.message,
+                        endpoint_key,
+                        panic.location.as_deref().unwrap_or_default()
+                    );
+                }
+                let payload = disconnect_payload(endpoint_key, &path, &stats, panic.as_ref());
+                push_ws_log("WS_DISCONNECT", endpoint_key, start_time.elapsed().as_millis() as i32, payload);
+            };
+
+            match log_ctx {
+                Some(log_ctx) => clog::LOG_CTX.scope(std::cell::RefCell::new(log_ctx), run).await,
+                None => run.await,
+            }
+        });
+
+        ctx.response = Some(response);
+        ctx.ok(StatusCode::SWITCHING_PROTOCOLS, ())
+    })
+}
+
+/// Payload of `WS_DISCONNECT`; `panicked` tells a handler that panicked from one that returned.
+pub(crate) fn disconnect_payload(
+    endpoint_key: &str,
+    path: &str,
+    stats: &WsStats,
+    panic: Option<&fuse_panic::FusePanic>,
+) -> serde_json::Value {
+    let close_code = stats.close_code.load(Ordering::Relaxed);
+    let mut payload = serde_json::json!({
+        "endpoint": endpoint_key,
+        "path": path,
+        "messages_received": stats.received.load(Ordering::Relaxed),
+        "messages_sent": stats.sent.load(Ordering::Relaxed),
+        "close_code": if close_code == 0 { None } else { Some(close_code) },
+        "panicked": panic.is_some(),
+    });
+    if let Some(panic) = panic {
+        payload["panic"] = serde_json::json!({ "message": panic.message });
+        if let Some(location) = &panic.location {
+            payload["location"] = serde_json::Value::String(location.clone());
+        }
+    }
+    payload
+}
+
+fn push_ws_log(log_type: &str, endpoint_key: &str, duration_ms: i32, payload: serde_json::Value) {
+    let status_code = StatusCode::SWITCHING_PROTOCOLS.as_u16() as i32;
+    if let Some(entry) = clog::new_log_entry(log_type, endpoint_key, duration_ms, status_code, payload.to_string()) {
+        clog::push_log(entry);
+    }
+}
@@ -0,0 +1,100 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::{FuseRContext, FuseResult};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+type BodyConverter = Box<dyn Fn(StatusCode, &(dyn Any + Send + Sync)) -> Option<Response> + Send + Sync>;
+
+static CONVERTERS: OnceLock<RwLock<HashMap<TypeId, BodyConverter>>> = OnceLock::new();
+
+fn get_converters() -> &'static RwLock<HashMap<TypeId, BodyConverter>> {
+    CONVERTERS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Registers how a handler body of type `T` is turned into a response, e.g. for protobuf or CSV bodies.
+/// A converter registered for a built-in type (`String`, `serde_json::Value`, ...) replaces the built-in one.
+pub fn add_body_converter<T, F>(f: F)
+where
+    T: Send + Sync + 'static,
+    F: Fn(StatusCode, &T) -> Response + Send + Sync + 'static,
+{
+    let converter: BodyConverter = Box::new(move |status, body| body.downcast_ref::<T>().map(|b| f(status, b)));
+    let mut converters = get_converters().write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    converters.insert(TypeId::of::<T>(), converter);
+}
+
+pub(crate) fn body_to_response(status: StatusCode, body: &(dyn Any + Send + Sync)) -> Option<Response> {
+    {
+        let converters = get_converters().read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(f) = converters.get(&body.type_id()) {
+            return f(status, body);
+        }
+    }
+
+    if let Some(text) = body.downcast_ref::<String>() {
+        let trimmed = text.trim();
+        if trimmed.starts_with("<") {
+            Some((status, axum::response::Html(text.clone())).into_response())
+        } else {
+            Some((status, text.clone()).into_response())
+        }
+    } else if let Some(html) = body.downcast_ref::<axum::response::Html<String>>() {
+        Some((status, html.clone()).into_response())
+    } else if let Some(text) = body.downcast_ref::<&'static str>() {
+        Some((status, (*text).to_string()).into_response())
+    } else if let Some(json) = body.downcast_ref::<serde_json::Value>() {
+        Some((status, axum::Json(json.clone())).into_response())
+    } else if let Some(bytes) = body.downcast_ref::<Vec<u8>>() {
+        Some((status, bytes.clone()).into_response())
+    } else if let Some(bytes) = body.downcast_ref::<axum::body::Bytes>() {
+        Some((status, bytes.clone()).into_response())
+    } else if let Some(e) = body.downcast_ref::<super::FuseError>() {
+        let mut res = e.to_response();
+        *res.status_mut() = status;
+        Some(res)
+    } else if body.is::<()>() {
+        Some(status.into_response())
+    } else {
+        None
+    }
+}
+
+impl FuseRContext {
+    /// Serializes `body` to JSON and returns it as a successful result.
+    #[inline(never)]
+    #[track_caller]
+    pub fn ok_json<T: Serialize + ?Sized>(&mut self, status: StatusCode, body: &T) -> FuseResult {
+        match serde_json::to_value(body) {
+            Ok(v) => self.ok(status, v),
+            Err(e) => self.json_serialize_failed(e),
+        }
+    }
+
+    /// Serializes `body` to JSON and returns it as an error result.
+    #[inline(never)]
+    #[track_caller]
+    pub fn err_json<T: Serialize + ?Sized>(&mut self, status: StatusCode, body: &T) -> FuseResult {
+        match serde_json::to_value(body) {
+            Ok(v) => self.err(status, v),
+            Err(e) => self.json_serialize_failed(e),
+        }
+    }
+
+    #[track_caller]
+    fn json_serialize_failed(&mut self, e: serde_json::Error) -> FuseResult {
+        self.fail(super::FuseError::internal("failed to serialize response body").with_source(e))
+    }
+}
@@ -10,68 +10,71 @@
 
 use super::FuseRContext;
 use axum::extract::ConnectInfo;
+use axum::http::HeaderMap;
 use std::net::IpAddr;
 
 impl FuseRContext {
     pub fn client_ip(&self) -> String {
-        let headers = self.req.headers();
+        let remote = self.req.extensions().get::<ConnectInfo<std::net::SocketAddr>>().map(|ConnectInfo(addr)| addr.ip());
+        client_ip(self.req.headers(), remote)
+    }
+}
 
-        // 1. X-Client-IP
-        if let Some(ip) = headers.get("x-client-ip").and_then(|v| v.to_str().ok()) {
-            return ip.trim().to_string();
-        }
+/// Client address from the proxy headers, falling back to the peer address of the connection.
+pub(crate) fn client_ip(headers: &HeaderMap, remote: Option<IpAddr>) -> String {
+    // 1. X-Client-IP
+    if let Some(ip) = headers.get("x-client-ip").and_then(|v| v.to_str().ok()) {
+        return ip.trim().to_string();
+    }
 
-        // 2. X-Original-Forwarded-For
-        if let Some(ip) =
-            headers.get("x-original-forwarded-for").and_then(|v| v.to_str().ok()).and_then(|val| self.retrieve_forwarded_ip(val))
-        {
-            return ip;
-        }
+    // 2. X-Original-Forwarded-For
+    if let Some(ip) = headers.get("x-original-forwarded-for").and_then(|v| v.to_str().ok()).and_then(retrieve_forwarded_ip) {
+        return ip;
+    }
 
-        // 3. X-Forwarded-For
-        if let Some(ip) = headers.get("x-forwarded-for").and_then(|v| v.to_str().ok()).and_then(|val| self.retrieve_forwarded_ip(val)) {
-            return ip;
-        }
+    // 3. X-Forwarded-For
+    if let Some(ip) = headers.get("x-forwarded-for").and_then(|v| v.to_str().ok()).and_then(retrieve_forwarded_ip) {
+        return ip;
+    }
 
-        // 4. Special headers
-        let special_headers = ["cf-connecting-ip", "fastly-client-ip", "true-client-ip", "x-real-ip"];
-        for header in special_headers {
-            if let Some(ip) = headers.get(header).and_then(|v| v.to_str().ok()) {
-                return ip.trim().to_string();
-            }
+    // 4. Special headers
+    let special_headers = ["cf-connecting-ip", "fastly-client-ip", "true-client-ip", "x-real-ip"];
+    for header in special_headers {
+        if let Some(ip) = headers.get(header).and_then(|v| v.to_str().ok()) {
+            return ip.trim().to_string();
         }
+    }
 
-        // 5. Other forwarded headers
-        let forwarded_headers = ["x-forwarded", "forwarded-for", "forwarded"];
-        for header in forwarded_headers {
-            if let Some(ip) = headers.get(header).and_then(|v| v.to_str().ok()).and_then(|val| self.retrieve_forwarded_ip(val)) {
-                return ip;
-            }
+    // 5. Other forwarded headers
+    let forwarded_headers = ["x-forwarded", "forwarded-for", "forwarded"];
+    for header in forwarded_headers {
+        if let Some(ip) = headers.get(header).and_then(|v| v.to_str().ok()).and_then(retrieve_forwarded_ip) {
+            return ip;
         }
-
-        // 6. Remote Address fallback
-        self.req.extensions().get::<ConnectInfo<std::net::SocketAddr>>().map(|ConnectInfo(addr)| addr.ip().to_string()).unwrap_or_default()
     }
 
-    fn retrieve_forwarded_ip(&self, header_val: &str) -> Option<String> {
-        let mut first_valid_ip = None;
-        for address in header_val.split(',') {
-            if let Ok(ip) = address.trim().parse::<IpAddr>() {
-                if !self.is_private_ip(ip) {
-                    return Some(ip.to_string());
-                }
-                if first_valid_ip.is_none() {
-                    first_valid_ip = Some(ip.to_string());
-                }
+    // 6. Remote Address fallback
+    remote.map(|ip| ip.to_string()).unwrap_or_default()
+}
+
+fn retrieve_forwarded_ip(header_val: &str) -> Option<String> {
+    let mut first_valid_ip = None;
+    for address in header_val.split(',') {
+        if let Ok(ip) = address.trim().parse::<IpAddr>() {
+            if !is_private_ip(ip) {
+                return Some(ip.to_string());
+            }
+            if first_valid_ip.is_none() {
+                first_valid_ip = Some(ip.to_string());
             }
         }
-        first_valid_ip
     }
+    first_valid_ip
+}
 
-    fn is_private_ip(&self, ip: IpAddr) -> bool {
-        match ip {
-            IpAddr::V4(ip) => ip.is_private() || ip.is_loopback() || ip.is_link_local(),
-            IpAddr::V6(ip) => ip.is_loopback() || (ip.segments()[0] & 0xfe00) == 0xfc00 || (ip.segments()[0] & 0xffc0) == 0xfe80,
-        }
+fn is_private_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_private() || ip.is_loopback() || ip.is_link_local(),
+        IpAddr::V6(ip) => ip.is_loopback() || (ip.segments()[0] & 0xfe00) == 0xfc00 || (ip.segments()[0] & 0xffc0) == 0xfe80,
     }
 }
@@ -0,0 +1,263 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::{FuseError, FuseRContext};
+use axum::body::Bytes;
+use axum::http::{StatusCode, header};
+use serde::de::DeserializeOwned;
+use std::any::Any;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+pub use multer::Error as MultipartError;
+
+/// Size limits in bytes applied while reading a multipart body.
+#[derive(Clone, Default)]
+pub struct MultipartLimits {
+    whole: Option<u64>,
+    per_part: Option<u64>,
+    parts: HashMap<String, u64>,
+}
+
+pub fn multipart_limits() -> MultipartLimits {
+    MultipartLimits::default()
+}
+
+impl MultipartLimits {
+    pub fn whole(mut self, limit: u64) -> Self {
+        self.whole = Some(limit);
+        self
+    }
+
+    pub fn per_part(mut self, limit: u64) -> Self {
+        self.per_part = Some(limit);
+        self
+    }
+
+    /// Overrides `per_part` for the part with the given field name.
+    pub fn part(mut self, name: &str, limit: u64) -> Self {
+        self.parts.insert(name.to_string(), limit);
+        self
+    }
+
+    fn constraints(&self) -> multer::Constraints {
+        let mut size = multer::SizeLimit::new();
+        if let Some(v) = self.whole {
+            size = size.whole_stream(v);
+        }
+        if let Some(v) = self.per_part {
+            size = size.per_field(v);
+        }
+        for (name, v) in &self.parts {
+            size = size.for_field(name.clone(), *v);
+        }
+        multer::Constraints::new().size_limit(size)
+    }
+}
+
+/// Multipart body read part by part, so file contents can be streamed instead of held in memory.
+pub struct FuseMultipart {
+    inner: multer::Multipart<'static>,
+}
+
+pub struct FusePart {
+    field: multer::Field<'static>,
+}
+
+/// Multipart body read fully: text parts by name and file parts in arrival order.
+#[derive(Debug, Default)]
+pub struct FuseFormData {
+    pub fields: HashMap<String, String>,
+    pub files: Vec<FuseFile>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FuseFile {
+    pub name: String,
+    pub filename: String,
+    pub content_type: Option<String>,
+    pub bytes: Bytes,
+}
+
+impl FuseMultipart {
+    pub async fn next_part(&mut self) -> Result<Option<FusePart>, MultipartError> {
+        Ok(self.inner.next_field().await?.map(|field| FusePart { field }))
+    }
+}
+
+impl FusePart {
+    pub fn name(&self) -> Option<&str> {
+        self.field.name()
+    }
+
+    pub fn filename(&self) -> Option<&str> {
+        self.field.file_name()
+    }
+
+    pub fn content_type(&self) -> Option<String> {
+        self.field.content_type().map(|m| m.to_string())
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.field.file_name().is_some()
+    }
+
+    /// Reads the next chunk of the part, `None` once the part is exhausted.
+    pub async fn chunk(&mut self) -> Result<Option<Bytes>, MultipartError> {
+        self.field.chunk().await
+    }
+
+    pub async fn bytes(self) -> Result<Bytes, MultipartError> {
+        self.field.bytes().await
+    }
+
+    pub async fn text(self) -> Result<String, MultipartError> {
+        self.field.text().await
+    }
+}
+
+impl FuseRContext {
+    /// Deserializes an `application/x-www-form-urlencoded` body into `T`.
+    #[inline(never)]
+    #[track_caller]
+    pub fn form<T: DeserializeOwned>(&mut self) -> Result<T, (StatusCode, Arc<dyn Any + Send + Sync>)> {
+        let bytes = self.body.as_deref().unwrap_or(&[]);
+        match serde_urlencoded::from_bytes::<T>(bytes) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(self.bad_request("invalid_form", e)),
+        }
+    }
+
+    /// Starts reading a `multipart/form-data` body part by part.
+    #[inline(never)]
+    #[track_caller]
+    pub fn multipart(&mut self, limits: MultipartLimits) -> Result<FuseMultipart, (StatusCode, Arc<dyn Any + Send + Sync>)> {
+        let content_type = self.req.headers().get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()).unwrap_or_default();
+        let boundary = match multer::parse_boundary(content_type) {
+            Ok(b) => b,
+            Err(e) => return Err(self.bad_request("invalid_multipart", e)),
+        };
+
+        let stream = self.body_stream();
+        Ok(FuseMultipart { inner: multer::Multipart::with_constraints(stream, boundary, limits.constraints()) })
+    }
+
+    /// Reads the whole `multipart/form-data` body, returning text fields and file parts.
+    #[inline(never)]
+    #[track_caller]
+    pub fn multipart_form(
+        &mut self,
+        limits: MultipartLimits,
+    ) -> impl Future<Output = Result<FuseFormData, (StatusCode, Arc<dyn Any + Send + Sync>)>> + Send + '_ {
+        let location = std::panic::Location::caller();
+        let multipart = self.multipart(limits);
+
+        async move {
+            let mut multipart = multipart?;
+            let mut form = FuseFormData::default();
+
+            let res: Result<(), MultipartError> = async {
+                while let Some(part) = multipart.next_part().await? {
+                    let name = part.name().unwrap_or_default().to_string();
+                    match part.filename().map(|s| s.to_string()) {
+                        Some(filename) => {
+                            let content_type = part.content_type();
+                            let bytes = part.bytes().await?;
+                            form.files.push(FuseFile { name, filename, content_type, bytes });
+                        }
+                        None => {
+                            let text = part.text().await?;
+                            form.fields.insert(name, text);
+                        }
+                    }
+                }
+                Ok(())
+            }
+            .await;
+
+            match res {
+                Ok(_) => Ok(form),
+                Err(e) => {
+                    let e = multipart_fuse_error(&e);
+                    self.update_location_and_backtrace(e.status, location);
+                    Err((e.status, Arc::new(e) as Arc<dyn Any + Send + Sync>))
+                }
+            }
+        }
+    }
+
+    /// Maps an error from `FuseMultipart`/`FusePart` to the standard 400 (or 413 when a size limit was hit) body.
+    #[inline(never)]
+    #[track_caller]
+    pub fn multipart_error(&mut self, e: MultipartError) -> (StatusCode, Arc<dyn Any + Send + Sync>) {
+        self.fail_val(multipart_fuse_error(&e))
+    }
+}
+
+fn multipart_fuse_error(e: &MultipartError) -> FuseError {
+    let status = match e {
+        MultipartError::FieldSizeExceeded { .. } | MultipartError::StreamSizeExceeded { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+        _ => StatusCode::BAD_REQUEST,
+    };
+    FuseError::new(status, "invalid_multipart", e.to_string())
+}
+
+/// Summarizes a buffered form body for clog: text fields as values, file parts as metadata only.
+pub(crate) async fn form_log_val(content_type: &str, bytes: &Bytes) -> Option<serde_json::Value> {
+    if content_type.starts_with("application/x-www-form-urlencoded") {
+        let pairs = serde_urlencoded::from_bytes::<Vec<(String, String)>>(bytes).ok()?;
+        let fields: serde_json::Map<String, serde_json::Value> =
+            pairs.into_iter().map(|(k, v)| (k, serde_json::Value::String(v))).collect();
+        return Some(serde_json::Value::Object(fields));
+    }
+
+    let boundary = multer::parse_boundary(content_type).ok()?;
+    let stream = futures_util::stream::once(futures_util::future::ready(Ok::<_, std::convert::Infallible>(bytes.clone())));
+    let mut multipart = multer::Multipart::new(stream, boundary);
+
+    let mut fields = serde_json::Map::new();
+    let mut files = Vec::new();
+    while let Ok(Some(mut field)) = multipart.next_field().await {
+        let name = field.name().unwrap_or_default().to_string();
+        let filename = field.file_name().map(|s| s.to_string());
+        let content_type = field.content_type().map(|m| m.to_string());
+
+        let mut size = 0;
+        let mut head = Vec::new();
+        while let Ok(Some(chunk)) = field.chunk().await {
+            size += chunk.len();
+            if filename.is_none() && head.len() < 1024 {
+                head.extend_from_slice(&chunk[..chunk.len().min(1024 - head.len())]);
+            }
+        }
+
+        match filename {
+            Some(filename) => files.push(serde_json::json!({
+                "name": name,
+                "filename": filename,
+                "content_type": content_type,
+                "size": size,
+            })),
+            None => {
+                let mut text = String::from_utf8_lossy(&head).to_string();
+                if size > head.len() {
+                    text.push_str("... [TRUNCATED]");
+                }
+                fields.insert(name, serde_json::Value::String(text));
+            }
+        }
+    }
+
+    Some(serde_json::json!({
+        "fields": fields,
+        "files": files,
+    }))
+}
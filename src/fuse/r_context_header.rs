@@ -0,0 +1,73 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::{FuseRContext, FuseResult};
+use axum::http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header};
+use axum::response::Response;
+use axum_extra::extract::cookie::{Cookie, CookieJar};
+
+impl FuseRContext {
+    /// Sets a response header, replacing any value set earlier for the same name.
+    pub fn set_header(&mut self, name: &str, value: &str) {
+        if let (Ok(k), Ok(v)) = (HeaderName::try_from(name), HeaderValue::from_str(value)) {
+            self.res_headers.insert(k, v);
+        }
+    }
+
+    /// Adds a response header, keeping values set earlier for the same name.
+    pub fn append_header(&mut self, name: &str, value: &str) {
+        if let (Ok(k), Ok(v)) = (HeaderName::try_from(name), HeaderValue::from_str(value)) {
+            self.res_headers.append(k, v);
+        }
+    }
+
+    /// Value of a request cookie.
+    pub fn cookie(&self, name: &str) -> Option<String> {
+        CookieJar::from_headers(self.req.headers()).get(name).map(|c| c.value().to_string())
+    }
+
+    pub fn set_cookie(&mut self, cookie: Cookie<'static>) {
+        if let Ok(v) = HeaderValue::from_str(&cookie.encoded().to_string()) {
+            self.res_headers.append(header::SET_COOKIE, v);
+        }
+    }
+
+    /// Expires a cookie on the client; `path` must match the path the cookie was set with.
+    pub fn remove_cookie(&mut self, name: &str, path: &str) {
+        let mut cookie = Cookie::build((name.to_string(), "")).path(path.to_string()).build();
+        cookie.make_removal();
+        self.set_cookie(cookie);
+    }
+
+    /// Redirects to `location`; `status` is normally 301, 302, 303, 307 or 308.
+    #[inline(never)]
+    #[track_caller]
+    pub fn redirect(&mut self, status: StatusCode, location: &str) -> FuseResult {
+        self.set_header(header::LOCATION.as_str(), location);
+        self.ok(status, ())
+    }
+}
+
+/// Applies headers collected on the context to the final response. `Set-Cookie` values are added
+/// next to the ones already on the response, any other header replaces the response value.
+pub(crate) fn merge_headers(response: &mut Response, res_headers: HeaderMap) {
+    let mut current: Option<HeaderName> = None;
+    for (name, value) in res_headers {
+        if let Some(name) = name {
+            if name != header::SET_COOKIE {
+                response.headers_mut().remove(&name);
+            }
+            current = Some(name);
+        }
+        if let Some(name) = &current {
+            response.headers_mut().append(name.clone(), value);
+        }
+    }
+}
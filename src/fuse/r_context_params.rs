@@ -0,0 +1,82 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::{FuseError, FuseRContext};
+use axum::extract::{FromRequestParts, Path, Query, RawPathParams};
+use axum::http::StatusCode;
+use futures_util::FutureExt;
+use serde::de::DeserializeOwned;
+use std::any::Any;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Arc;
+
+impl FuseRContext {
+    /// Reads a single path segment declared as `{name}` in the endpoint key, e.g. `"GET: /users/{uid}"`.
+    #[inline(never)]
+    #[track_caller]
+    pub fn path_param<T>(&mut self, name: &str) -> Result<T, (StatusCode, Arc<dyn Any + Send + Sync>)>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: Display,
+    {
+        let raw = match self.extract_parts::<RawPathParams>() {
+            Ok(params) => params.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string()),
+            Err(e) => return Err(self.bad_request("invalid_path_param", e)),
+        };
+
+        match raw {
+            Some(v) => v.parse::<T>().map_err(|e| self.bad_request("invalid_path_param", format!("{}: {}", name, e))),
+            None => Err(self.bad_request("invalid_path_param", format!("{}: missing path parameter", name))),
+        }
+    }
+
+    /// Deserializes all path parameters into `T`, by name for structs or by position for tuples.
+    #[inline(never)]
+    #[track_caller]
+    pub fn path<T: DeserializeOwned + Send + 'static>(&mut self) -> Result<T, (StatusCode, Arc<dyn Any + Send + Sync>)> {
+        match self.extract_parts::<Path<T>>() {
+            Ok(Path(v)) => Ok(v),
+            Err(e) => Err(self.bad_request("invalid_path_param", e)),
+        }
+    }
+
+    /// Deserializes the query string into `T`, using the same key/value pairs as `query()`.
+    #[inline(never)]
+    #[track_caller]
+    pub fn query_as<T: DeserializeOwned>(&mut self) -> Result<T, (StatusCode, Arc<dyn Any + Send + Sync>)> {
+        match Query::<T>::try_from_uri(self.req.uri()) {
+            Ok(Query(v)) => Ok(v),
+            Err(e) => Err(self.bad_request("invalid_query_param", e.body_text())),
+        }
+    }
+
+    #[track_caller]
+    pub(crate) fn bad_request(&mut self, error: &str, message: impl Display) -> (StatusCode, Arc<dyn Any + Send + Sync>) {
+        self.fail_val(FuseError::new(StatusCode::BAD_REQUEST, error, message.to_string()))
+    }
+
+    pub(crate) fn extract_parts<E>(&mut self) -> Result<E, String>
+    where
+        E: FromRequestParts<()>,
+        E::Rejection: Display,
+    {
+        // axum extractors for path params are async in signature only; they never await on `()` state.
+        let (mut parts, body) = std::mem::take(&mut self.req).into_parts();
+        let res = E::from_request_parts(&mut parts, &()).now_or_never();
+        self.req = axum::extract::Request::from_parts(parts, body);
+
+        match res {
+            Some(Ok(v)) => Ok(v),
+            Some(Err(e)) => Err(e.to_string()),
+            None => Err("path parameters are not available".to_string()),
+        }
+    }
+}
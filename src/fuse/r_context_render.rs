@@ -0,0 +1,43 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::{FuseError, FuseRContext, FuseResult};
+use crate::clog;
+use askama::Template;
+use axum::http::StatusCode;
+use axum::response::Html;
+
+impl FuseRContext {
+    /// Renders an askama template as an html response. A render error becomes a 500, logged with
+    /// the template name and the location of the `render` call.
+    #[inline(never)]
+    #[track_caller]
+    pub fn render<T: Template>(&mut self, status: StatusCode, template: &T) -> FuseResult {
+        match template.render() {
+            Ok(html) => self.ok(status, Html(html)),
+            Err(e) => {
+                let name = std::any::type_name::<T>();
+                let loc = std::panic::Location::caller();
+                tracing::error!("failed to render template {}: {} [{}:{}]", name, e, loc.file(), loc.line());
+                clog::error(
+                    self.req.uri().path(),
+                    serde_json::json!({
+                        "error": "failed to render template",
+                        "template": name,
+                        "message": e.to_string(),
+                        "location": format!("{}:{}", loc.file(), loc.line()),
+                    }),
+                );
+
+                self.fail(FuseError::internal("failed to render template").with_source(e))
+            }
+        }
+    }
+}
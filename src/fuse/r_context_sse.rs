@@ -0,0 +1,72 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::{FuseRContext, FuseResult};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::response::sse::{KeepAlive, Sse};
+use futures_util::{Stream, StreamExt};
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+pub use axum::response::sse::Event as SseEvent;
+
+static SSE_KEEP_ALIVE: OnceLock<Duration> = OnceLock::new();
+
+/// Interval of the keep-alive comments, from `RMOD_SSE_KEEP_ALIVE` (e.g. `15s`, the default).
+fn sse_keep_alive() -> Duration {
+    *SSE_KEEP_ALIVE.get_or_init(|| crate::time::to_duration(&crate::util::env::string_or("RMOD_SSE_KEEP_ALIVE", "15s")))
+}
+
+/// Number of events sent on an SSE response, attached to the response so clog can report it.
+#[derive(Default)]
+pub(crate) struct SseStats {
+    events: AtomicU64,
+}
+
+impl SseStats {
+    pub fn to_json(&self, bytes: usize) -> serde_json::Value {
+        serde_json::json!({
+            "sse": true,
+            "events": self.events.load(Ordering::Relaxed),
+            "bytes": bytes,
+        })
+    }
+}
+
+impl FuseRContext {
+    /// Responds with a `text/event-stream` of `events`. Keep-alive comments are sent while the stream is idle,
+    /// and the stream ends when the service starts shutting down.
+    #[inline(never)]
+    #[track_caller]
+    pub fn ok_sse<S>(&mut self, events: S) -> FuseResult
+    where
+        S: Stream<Item = SseEvent> + Send + 'static,
+    {
+        let stats = Arc::new(SseStats::default());
+        let counter = stats.clone();
+        let mut shutdown_rx = crate::util::lifecycle::subscribe();
+        let events = events
+            .map(move |event| {
+                counter.events.fetch_add(1, Ordering::Relaxed);
+                Ok::<_, Infallible>(event)
+            })
+            .take_until(async move {
+                let _ = shutdown_rx.recv().await;
+            });
+
+        let mut response = Sse::new(events).keep_alive(KeepAlive::new().interval(sse_keep_alive())).into_response();
+        response.extensions_mut().insert(stats);
+        self.response = Some(response);
+        self.ok(StatusCode::OK, ())
+    }
+}
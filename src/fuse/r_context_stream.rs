@@ -0,0 +1,126 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::{FuseRContext, FuseResult};
+use axum::body::{Body, BodyDataStream, Bytes};
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use futures_util::{Stream, StreamExt};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll};
+
+static STREAM_LOG_HEAD_SIZE: OnceLock<usize> = OnceLock::new();
+
+pub(crate) fn stream_log_head_size() -> usize {
+    *STREAM_LOG_HEAD_SIZE
+        .get_or_init(|| std::env::var("RMOD_STREAM_LOG_HEAD_SIZE").ok().and_then(|s| s.parse::<usize>().ok()).unwrap_or(1024))
+}
+
+/// First bytes and total size of a body that went through a `BodyTap`.
+#[derive(Default)]
+pub(crate) struct TapState {
+    pub head: Vec<u8>,
+    pub total: usize,
+}
+
+impl TapState {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "streaming": true,
+            "bytes": self.total,
+            "truncated": self.total > self.head.len(),
+            "head": String::from_utf8_lossy(&self.head),
+        })
+    }
+}
+
+type TapDone = Box<dyn FnOnce(&TapState) + Send>;
+
+/// Passes a body through untouched while keeping its head and byte count for logging.
+pub(crate) struct BodyTap {
+    inner: BodyDataStream,
+    state: Arc<Mutex<TapState>>,
+    head_limit: usize,
+    on_done: Option<TapDone>,
+}
+
+impl BodyTap {
+    pub fn new(body: Body, state: Arc<Mutex<TapState>>, on_done: Option<TapDone>) -> Self {
+        Self { inner: body.into_data_stream(), state, head_limit: stream_log_head_size(), on_done }
+    }
+
+    fn finish(&mut self) {
+        if let Some(f) = self.on_done.take() {
+            let state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            f(&state);
+        }
+    }
+}
+
+impl Stream for BodyTap {
+    type Item = Result<Bytes, axum::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let res = self.inner.poll_next_unpin(cx);
+        match &res {
+            Poll::Ready(Some(Ok(chunk))) => {
+                let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                state.total += chunk.len();
+                let room = self.head_limit.saturating_sub(state.head.len());
+                if room > 0 {
+                    state.head.extend_from_slice(&chunk[..room.min(chunk.len())]);
+                }
+            }
+            Poll::Ready(None) | Poll::Ready(Some(Err(_))) => self.finish(),
+            Poll::Pending => {}
+        }
+        res
+    }
+}
+
+impl Drop for BodyTap {
+    fn drop(&mut self) {
+        // Client went away before the body finished; still report what was sent.
+        self.finish();
+    }
+}
+
+pub(crate) fn stream_request_meta(headers: &HeaderMap) -> serde_json::Value {
+    serde_json::json!({
+        "streaming": true,
+        "content_type": headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()),
+        "content_length": headers.get(header::CONTENT_LENGTH).and_then(|v| v.to_str().ok()).and_then(|v| v.parse::<u64>().ok()),
+    })
+}
+
+impl FuseRContext {
+    /// Takes the request body as a stream of chunks. Only endpoints registered with `opt().stream()`
+    /// receive an unbuffered body; for other endpoints the stream yields the already-buffered bytes.
+    pub fn body_stream(&mut self) -> BodyDataStream {
+        std::mem::take(self.req.body_mut()).into_data_stream()
+    }
+
+    /// Responds with a body produced by `stream`, sent to the client as chunks arrive.
+    #[inline(never)]
+    #[track_caller]
+    pub fn ok_stream<S, E>(&mut self, status: StatusCode, content_type: &str, stream: S) -> FuseResult
+    where
+        S: Stream<Item = Result<Bytes, E>> + Send + 'static,
+        E: Into<axum::BoxError>,
+    {
+        let mut response = axum::response::Response::new(Body::from_stream(stream));
+        *response.status_mut() = status;
+        if let Ok(v) = HeaderValue::from_str(content_type) {
+            response.headers_mut().insert(header::CONTENT_TYPE, v);
+        }
+        self.response = Some(response);
+        self.ok(status, ())
+    }
+}
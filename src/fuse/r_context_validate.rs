@@ -0,0 +1,54 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::FuseRContext;
+use crate::validate::{FieldError, Validate};
+use axum::http::StatusCode;
+use serde::de::DeserializeOwned;
+use std::any::Any;
+use std::sync::Arc;
+
+impl FuseRContext {
+    /// Deserializes the JSON body into `T` and runs its `Validate` checks. Malformed JSON is a 400
+    /// `invalid_json`; a body that does not fit `T` or fails validation is a 422 listing the field errors.
+    #[inline(never)]
+    #[track_caller]
+    pub fn json_valid<T: DeserializeOwned + Validate>(&mut self) -> Result<T, (StatusCode, Arc<dyn Any + Send + Sync>)> {
+        let val = match self.json::<T>() {
+            Ok(v) => v,
+            Err(e) => {
+                let path = e.path().to_string();
+                let inner = e.into_inner();
+                let message = strip_position(&inner.to_string());
+                if inner.is_syntax() || inner.is_eof() {
+                    return Err(self.bad_request("invalid_json", message));
+                }
+                return Err(self.unprocessable(vec![FieldError { path, code: "invalid", message }]));
+            }
+        };
+
+        match val.validate() {
+            Ok(_) => Ok(val),
+            Err(errors) => Err(self.unprocessable(errors)),
+        }
+    }
+
+    #[track_caller]
+    fn unprocessable(&mut self, errors: Vec<FieldError>) -> (StatusCode, Arc<dyn Any + Send + Sync>) {
+        self.fail_val(errors)
+    }
+}
+
+/// serde_json appends ` at line 1 column 12` to its messages; the JSON path says where instead.
+pub(crate) fn strip_position(msg: &str) -> String {
+    let msg = msg.split(" at line ").next().unwrap_or(msg);
+    let msg = msg.split(" at column ").next().unwrap_or(msg);
+    msg.to_string()
+}
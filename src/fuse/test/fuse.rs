@@ -0,0 +1,1609 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::*;
+use futures_util::StreamExt;
+use std::time::Duration;
+use tower::ServiceExt;
+
+fn defer(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let status = ctx.res_status.unwrap_or(StatusCode::NOT_FOUND);
+        let body = ctx.res_body.clone().unwrap_or_else(|| Arc::new(String::new()));
+        if status.is_success() { Ok((status, body)) } else { Err((status, body)) }
+    })
+}
+
+async fn call(fuse: Fuse, method: &str, uri: &str, body: &str) -> (StatusCode, String) {
+    let req = Request::builder().method(method).uri(uri).body(Body::from(body.to_string())).unwrap();
+    let res = fuse.router.oneshot(req).await.unwrap();
+    let status = res.status();
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8_lossy(&bytes).to_string())
+}
+
+#[derive(serde::Deserialize)]
+struct UserPath {
+    uid: String,
+    seq: u32,
+}
+
+#[derive(serde::Deserialize)]
+struct Paging {
+    page: u32,
+    size: Option<u32>,
+}
+
+fn get_user(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let uid: String = ctx.path_param("uid")?;
+        let seq: u32 = ctx.path_param("seq")?;
+        let p = ctx.path::<UserPath>()?;
+        ctx.ok(StatusCode::OK, format!("{}:{}:{}:{}", uid, seq, p.uid, p.seq))
+    })
+}
+
+fn list_users(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let q = ctx.query_as::<Paging>()?;
+        ctx.ok(StatusCode::OK, format!("{}:{}", q.page, q.size.unwrap_or(10)))
+    })
+}
+
+#[tokio::test]
+async fn test_path_params() {
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /users/{uid}/{seq}" => get_user));
+
+    let (status, body) = call(fuse, "GET", "/users/a%20b/7", "").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "a b:7:a b:7");
+}
+
+#[tokio::test]
+async fn test_path_param_invalid() {
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /users/{uid}/{seq}" => get_user));
+
+    let (status, body) = call(fuse, "GET", "/users/abc/x", "").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["code"], "invalid_path_param");
+    assert!(body["detail"].as_str().unwrap().starts_with("seq:"));
+}
+
+#[tokio::test]
+async fn test_query_as() {
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /users" => list_users));
+    let (status, body) = call(fuse, "GET", "/users?page=2&size=50", "").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "2:50");
+
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /users" => list_users));
+    let (status, body) = call(fuse, "GET", "/users?size=50", "").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["code"], "invalid_query_param");
+}
+
+fn echo_method(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let method = ctx.req.method().to_string();
+        ctx.ok(StatusCode::OK, method)
+    })
+}
+
+#[test]
+fn test_parse_endpoint_key() {
+    let (methods, path) = parse_endpoint_key("GET|POS: /x/{id}").unwrap();
+    assert_eq!(methods.iter().map(|(n, _)| *n).collect::<Vec<_>>(), vec!["GET", "POST"]);
+    assert_eq!(path, "/x/{id}");
+
+    let (methods, _) = parse_endpoint_key("ANY: /x").unwrap();
+    assert_eq!(methods.len(), ANY_METHODS.len());
+
+    assert!(parse_endpoint_key("GET /x").is_err());
+    assert!(parse_endpoint_key("FETCH: /x").is_err());
+    assert!(parse_endpoint_key("GET: x").is_err());
+    assert!(parse_endpoint_key("GET|GET: /x").is_err());
+    assert!(parse_endpoint_key("GET|: /x").is_err());
+}
+
+#[test]
+fn test_endpoint_collisions() {
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /x" => echo_method, "GET|POST: /x" => echo_method));
+    assert_eq!(fuse.errors.len(), 1);
+    assert!(fuse.errors[0].contains("collides"));
+
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /x/{id}" => echo_method));
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("POST: /x/{uid}" => echo_method, "BAD /y" => echo_method));
+    assert_eq!(fuse.errors.len(), 2);
+
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /x/{id}" => echo_method, "PUT|DEL: /x/{id}" => echo_method));
+    assert!(fuse.errors.is_empty());
+}
+
+#[tokio::test]
+async fn test_multi_method_endpoint() {
+    for method in ["GET", "POST", "OPTIONS", "TRACE"] {
+        let mut fuse = Fuse::new();
+        fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET|POST: /x" => echo_method, "OPTIONS|TRACE: /x" => echo_method));
+        let (status, body) = call(fuse, method, "/x", "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, method);
+    }
+
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET|POST: /x" => echo_method));
+    let (status, _) = call(fuse, "PUT", "/x", "").await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("ANY: /x" => echo_method));
+    let (status, body) = call(fuse, "PATCH", "/x", "").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "PATCH");
+}
+
+fn upload(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        use futures_util::StreamExt;
+        let mut total = 0;
+        let mut stream = ctx.body_stream();
+        while let Some(chunk) = stream.next().await {
+            total += chunk.map(|c| c.len()).unwrap_or_default();
+        }
+
+        let chunks = (0..3).map(move |i| Ok::<_, std::io::Error>(axum::body::Bytes::from(format!("{}:{};", i, total))));
+        ctx.ok_stream(StatusCode::OK, "text/plain", futures_util::stream::iter(chunks))
+    })
+}
+
+#[tokio::test]
+async fn test_stream_endpoint() {
+    let mut fuse = Fuse::new();
+    fuse.option("POST: /upload", opt().stream());
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("POST: /upload" => upload));
+    assert!(fuse.errors.is_empty());
+
+    let (status, body) = call(fuse, "POST", "/upload", "0123456789").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "0:10;1:10;2:10;");
+}
+
+#[tokio::test]
+async fn test_body_tap() {
+    use futures_util::StreamExt;
+
+    let done = Arc::new(Mutex::new(None));
+    let done_clone = done.clone();
+    let state = Arc::new(Mutex::new(TapState::default()));
+    let chunks = vec![Ok::<_, std::io::Error>("a".repeat(1000)), Ok("b".repeat(1000))];
+    let mut tap = BodyTap::new(
+        Body::from_stream(futures_util::stream::iter(chunks)),
+        state,
+        Some(Box::new(move |s: &TapState| *done_clone.lock().unwrap() = Some(s.to_json()))),
+    );
+
+    let mut received = 0;
+    while let Some(chunk) = tap.next().await {
+        received += chunk.unwrap().len();
+    }
+    assert_eq!(received, 2000);
+
+    let log = done.lock().unwrap().clone().unwrap();
+    assert_eq!(log["bytes"], 2000);
+    assert_eq!(log["truncated"], true);
+    assert_eq!(log["head"].as_str().unwrap().len(), 1024);
+}
+
+const MULTIPART_BODY: &str = "--XBOUND\r\nContent-Disposition: form-data; name=\"nik\"\r\n\r\n3171\r\n--XBOUND\r\nContent-Disposition: form-data; name=\"ktp\"; filename=\"ktp.jpg\"\r\nContent-Type: image/jpeg\r\n\r\nJPEGDATA\r\n--XBOUND--\r\n";
+
+#[derive(serde::Deserialize)]
+struct Callback {
+    order_id: String,
+    amount: u64,
+}
+
+fn callback(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let form = ctx.form::<Callback>()?;
+        ctx.ok(StatusCode::OK, format!("{}:{}", form.order_id, form.amount))
+    })
+}
+
+fn kyc_upload(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let limit = if ctx.req.uri().path().ends_with("/small") { 4 } else { 1024 };
+        let form = ctx.multipart_form(multipart_limits().part("ktp", limit)).await?;
+        let file = &form.files[0];
+        let res = format!("{}:{}:{}:{:?}:{}", form.fields["nik"], file.name, file.filename, file.content_type, file.bytes.len());
+        ctx.ok(StatusCode::OK, res)
+    })
+}
+
+fn kyc_upload_limited(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let mut multipart = ctx.multipart(multipart_limits().per_part(4))?;
+        while let Some(part) = multipart.next_part().await.map_err(|e| ctx.multipart_error(e))? {
+            part.bytes().await.map_err(|e| ctx.multipart_error(e))?;
+        }
+        ctx.ok(StatusCode::OK, "ok")
+    })
+}
+
+async fn call_with(fuse: Fuse, method: &str, uri: &str, content_type: &str, body: &str) -> (StatusCode, String) {
+    let req =
+        Request::builder().method(method).uri(uri).header(header::CONTENT_TYPE, content_type).body(Body::from(body.to_string())).unwrap();
+    let res = fuse.router.oneshot(req).await.unwrap();
+    let status = res.status();
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8_lossy(&bytes).to_string())
+}
+
+#[tokio::test]
+async fn test_form() {
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("POST: /callback" => callback));
+    let (status, body) = call_with(fuse, "POST", "/callback", "application/x-www-form-urlencoded", "order_id=A%2F1&amount=500").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "A/1:500");
+
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("POST: /callback" => callback));
+    let (status, body) = call_with(fuse, "POST", "/callback", "application/x-www-form-urlencoded", "order_id=A1").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.contains("invalid_form"));
+}
+
+#[tokio::test]
+async fn test_multipart() {
+    let content_type = "multipart/form-data; boundary=XBOUND";
+
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("POST: /kyc" => kyc_upload_limited));
+    let (status, body) = call_with(fuse, "POST", "/kyc", content_type, MULTIPART_BODY).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert!(body.contains("invalid_multipart"));
+
+    for stream in [false, true] {
+        let mut fuse = Fuse::new();
+        if stream {
+            fuse.option("POST: /kyc", opt().stream());
+        }
+        fuse.endpoints(defer, vec![], crate::fuse_endpoints!("POST: /kyc" => kyc_upload));
+        let (status, body) = call_with(fuse, "POST", "/kyc", content_type, MULTIPART_BODY).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "3171:ktp:ktp.jpg:Some(\"image/jpeg\"):8");
+    }
+
+    let mut fuse = Fuse::new();
+    fuse.option("POST: /kyc/small", opt().stream());
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("POST: /kyc/small" => kyc_upload));
+    let (status, body) = call_with(fuse, "POST", "/kyc/small", content_type, MULTIPART_BODY).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert!(body.contains("invalid_multipart"));
+
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("POST: /kyc" => kyc_upload));
+    let (status, _) = call_with(fuse, "POST", "/kyc", "text/plain", MULTIPART_BODY).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_form_log_val() {
+    let bytes = axum::body::Bytes::from(MULTIPART_BODY);
+    let val = r_context_form::form_log_val("multipart/form-data; boundary=XBOUND", &bytes).await.unwrap();
+    assert_eq!(val["fields"]["nik"], "3171");
+    assert_eq!(val["files"][0]["filename"], "ktp.jpg");
+    assert_eq!(val["files"][0]["content_type"], "image/jpeg");
+    assert_eq!(val["files"][0]["size"], 8);
+    assert!(!val.to_string().contains("JPEGDATA"));
+
+    let bytes = axum::body::Bytes::from("a=1&b=x%20y");
+    let val = r_context_form::form_log_val("application/x-www-form-urlencoded", &bytes).await.unwrap();
+    assert_eq!(val, serde_json::json!({"a": "1", "b": "x y"}));
+
+    assert!(r_context_form::form_log_val("application/json", &bytes).await.is_none());
+}
+
+#[derive(serde::Serialize)]
+struct Account {
+    uid: String,
+    balance: u64,
+}
+
+struct CsvReport(Vec<(String, u64)>);
+
+struct Unconverted;
+
+fn get_account(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        match ctx.req.uri().path() {
+            "/account" => ctx.ok_json(StatusCode::CREATED, &Account { uid: "a1".to_string(), balance: 10 }),
+            "/account/csv" => ctx.ok(StatusCode::OK, CsvReport(vec![("a1".to_string(), 10), ("a2".to_string(), 20)])),
+            _ => ctx.ok(StatusCode::OK, Unconverted),
+        }
+    })
+}
+
+#[tokio::test]
+async fn test_response_body_conversion() {
+    add_body_converter::<CsvReport, _>(|status, report| {
+        let csv: String = report.0.iter().map(|(k, v)| format!("{},{}\n", k, v)).collect();
+        (status, [(header::CONTENT_TYPE, "text/csv")], csv).into_response()
+    });
+
+    let mapping =
+        || crate::fuse_endpoints!("GET: /account" => get_account, "GET: /account/csv" => get_account, "GET: /account/raw" => get_account);
+
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], mapping());
+    let (status, body) = call(fuse, "GET", "/account", "").await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body, r#"{"uid":"a1","balance":10}"#);
+
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], mapping());
+    let (status, body) = call(fuse, "GET", "/account/csv", "").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "a1,10\na2,20\n");
+
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], mapping());
+    let (status, body) = call(fuse, "GET", "/account/raw", "").await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(body.contains("no converter"));
+}
+
+fn tag_request(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        ctx.set_header("x-request-tag", "pre");
+        ctx.ok(StatusCode::OK, ())
+    })
+}
+
+fn login(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let session = ctx.cookie("session").unwrap_or_default();
+        ctx.set_header("x-request-tag", "handler");
+        ctx.append_header("x-trail", "a");
+        ctx.append_header("x-trail", "b");
+        ctx.set_cookie(Cookie::build(("token", "t 1")).path("/").http_only(true).build());
+        ctx.remove_cookie("session", "/");
+        ctx.redirect(StatusCode::SEE_OTHER, &format!("/home?from={}", session))
+    })
+}
+
+fn defer_with_header(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        ctx.set_header("x-audit", "1");
+        defer(ctx).await
+    })
+}
+
+#[tokio::test]
+async fn test_response_headers_and_cookies() {
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer_with_header, crate::fuse_handlers!(tag_request), crate::fuse_endpoints!("POST: /login" => login));
+
+    let req = Request::builder().method("POST").uri("/login").header(header::COOKIE, "session=s1; other=x").body(Body::empty()).unwrap();
+    let res = fuse.router.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::SEE_OTHER);
+
+    let headers = res.headers();
+    assert_eq!(headers[header::LOCATION], "/home?from=s1");
+    assert_eq!(headers["x-request-tag"], "handler");
+    assert_eq!(headers["x-audit"], "1");
+    assert_eq!(headers.get_all("x-trail").iter().collect::<Vec<_>>(), vec!["a", "b"]);
+
+    let cookies: Vec<&str> = headers.get_all(header::SET_COOKIE).iter().map(|v| v.to_str().unwrap()).collect();
+    assert_eq!(cookies.len(), 2);
+    assert!(cookies[0].starts_with("token=t%201") && cookies[0].contains("HttpOnly"));
+    assert!(cookies[1].starts_with("session=") && cookies[1].contains("Max-Age=0"));
+}
+
+fn trail(ctx: &mut FuseRContext, step: &str) {
+    let prev = ctx.get::<String>("trail").map(|s| s.to_string()).unwrap_or_default();
+    ctx.set("trail", format!("{}{}", prev, step));
+}
+
+fn pre_partner(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        trail(ctx, "partner>");
+        ctx.ok(StatusCode::OK, ())
+    })
+}
+
+fn pre_admin(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        trail(ctx, "admin>");
+        if ctx.req.headers().contains_key("x-deny") {
+            return ctx.err(StatusCode::FORBIDDEN, "denied");
+        }
+        ctx.ok(StatusCode::OK, ())
+    })
+}
+
+fn show_trail(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        trail(ctx, "handler");
+        let trail = ctx.get::<String>("trail").map(|s| s.to_string()).unwrap_or_default();
+        ctx.ok(StatusCode::OK, trail)
+    })
+}
+
+fn defer_audit(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        ctx.set_header("x-audit", "admin");
+        defer(ctx).await
+    })
+}
+
+#[tokio::test]
+async fn test_route_groups() {
+    let build = || {
+        let mut fuse = Fuse::new();
+        fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /ping" => show_trail));
+        fuse.group("/partner/", defer, crate::fuse_handlers!(pre_partner), |g| {
+            g.endpoints(crate::fuse_endpoints!("GET: /" => show_trail, "GET: /orders" => show_trail));
+            g.group("admin", Some(defer_audit), crate::fuse_handlers!(pre_admin), |g| {
+                g.option("POST: /upload", opt().stream());
+                g.endpoints(crate::fuse_endpoints!("GET: /users/{uid}" => show_trail, "POST: /upload" => show_trail));
+            });
+        });
+        assert!(fuse.errors.is_empty(), "{:?}", fuse.errors);
+        assert!(fuse.options.contains_key("POST: /partner/admin/upload"));
+        fuse
+    };
+
+    let (_, body) = call(build(), "GET", "/ping", "").await;
+    assert_eq!(body, "handler");
+
+    let (_, body) = call(build(), "GET", "/partner", "").await;
+    assert_eq!(body, "partner>handler");
+
+    let (_, body) = call(build(), "GET", "/partner/orders", "").await;
+    assert_eq!(body, "partner>handler");
+
+    let req = Request::builder().uri("/partner/admin/users/u1").body(Body::empty()).unwrap();
+    let res = build().router.oneshot(req).await.unwrap();
+    assert_eq!(res.headers()["x-audit"], "admin");
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(bytes, "partner>admin>handler");
+
+    let req = Request::builder().uri("/partner/admin/users/u1").header("x-deny", "1").body(Body::empty()).unwrap();
+    let res = build().router.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+    let mut fuse = Fuse::new();
+    fuse.group("/partner", defer, vec![], |g| g.endpoints(crate::fuse_endpoints!("GET: orders" => show_trail)));
+    assert_eq!(fuse.errors.len(), 1);
+}
+
+fn big_text(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        ctx.set_header("x-frame-options", "SAMEORIGIN");
+        ctx.ok(StatusCode::OK, "fuse ".repeat(200))
+    })
+}
+
+#[tokio::test]
+async fn test_layers() {
+    let build = || {
+        let mut fuse = Fuse::new();
+        fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /text" => big_text));
+        fuse.cors(cors().origins(&["https://app.example.com"]).methods(&["GET", "POST"]).credentials().max_age(Duration::from_secs(600)));
+        fuse.compression();
+        fuse.security_headers(security_headers().hsts(Duration::from_secs(3600), false));
+        assert!(fuse.errors.is_empty(), "{:?}", fuse.errors);
+        fuse.into_router()
+    };
+
+    let req = Request::builder()
+        .method("OPTIONS")
+        .uri("/text")
+        .header("origin", "https://app.example.com")
+        .header("access-control-request-method", "POST")
+        .body(Body::empty())
+        .unwrap();
+    let res = build().oneshot(req).await.unwrap();
+    assert_eq!(res.headers()["access-control-allow-origin"], "https://app.example.com");
+    assert_eq!(res.headers()["access-control-allow-credentials"], "true");
+    assert_eq!(res.headers()["access-control-max-age"], "600");
+
+    let req = Request::builder().uri("/text").header("origin", "https://evil.example.com").body(Body::empty()).unwrap();
+    let res = build().oneshot(req).await.unwrap();
+    assert!(res.headers().get("access-control-allow-origin").is_none());
+
+    let req = Request::builder().uri("/text").header("accept-encoding", "gzip").body(Body::empty()).unwrap();
+    let res = build().oneshot(req).await.unwrap();
+    assert_eq!(res.headers()["content-encoding"], "gzip");
+    assert_eq!(res.headers()["strict-transport-security"], "max-age=3600");
+    assert_eq!(res.headers()["x-content-type-options"], "nosniff");
+    assert_eq!(res.headers()["x-frame-options"], "SAMEORIGIN");
+
+    let mut fuse = Fuse::new();
+    fuse.cors(cors().origins(&["*"]).credentials());
+    fuse.security_headers(security_headers().frame_options("DENY\n"));
+    assert_eq!(fuse.errors.len(), 2);
+}
+
+#[tokio::test]
+async fn test_static_and_spa() {
+    let dir = std::env::temp_dir().join(format!("fuse-static-{}", crate::uid::new()));
+    std::fs::create_dir_all(dir.join("assets")).unwrap();
+    std::fs::write(dir.join("index.html"), "<html>app</html>").unwrap();
+    std::fs::write(dir.join("assets/app.js"), "console.log(1)").unwrap();
+    let dir_str = dir.to_string_lossy().to_string();
+
+    let build = || {
+        let mut fuse = Fuse::new();
+        fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /api/ping" => show_trail));
+        fuse.static_dir("/admin", &dir_str);
+        fuse.spa(&dir_str, &["/api"]);
+        assert!(fuse.errors.is_empty(), "{:?}", fuse.errors);
+        fuse.into_router()
+    };
+
+    let req = Request::builder().uri("/admin/assets/app.js").body(Body::empty()).unwrap();
+    let res = build().oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res.headers()["content-type"].to_str().unwrap().contains("javascript"));
+    assert_eq!(res.headers()["cache-control"], "public, max-age=3600");
+    let etag = res.headers()["etag"].clone();
+
+    let req = Request::builder().uri("/admin/assets/app.js").header("if-none-match", etag).body(Body::empty()).unwrap();
+    let res = build().oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+
+    let req = Request::builder().uri("/orders/42").body(Body::empty()).unwrap();
+    let res = build().oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["cache-control"], "no-cache");
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(bytes, "<html>app</html>");
+
+    for uri in ["/assets/missing.js", "/api/unknown"] {
+        let req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let res = build().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND, "{}", uri);
+    }
+
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /admin/{id}" => show_trail));
+    fuse.static_dir("/admin", &dir_str);
+    fuse.static_dir("/", &dir_str);
+    fuse.spa(&dir_str, &[]);
+    assert_eq!(fuse.errors.len(), 2, "{:?}", fuse.errors);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[derive(askama::Template)]
+#[template(source = "<h1>Hello {{ name }}</h1>", ext = "html")]
+struct HelloPage {
+    name: String,
+}
+
+struct Broken;
+
+impl std::fmt::Display for Broken {
+    fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Err(std::fmt::Error)
+    }
+}
+
+#[derive(askama::Template)]
+#[template(source = "{{ value }}", ext = "html")]
+struct BrokenPage {
+    value: Broken,
+}
+
+fn hello_page(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let name = ctx.path_param::<String>("name")?;
+        ctx.render(StatusCode::OK, &HelloPage { name })
+    })
+}
+
+fn broken_page(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move { ctx.render(StatusCode::OK, &BrokenPage { value: Broken }) })
+}
+
+fn render_then_fail(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let _ = ctx.render(StatusCode::OK, &HelloPage { name: "draft".to_string() });
+        ctx.err(StatusCode::BAD_REQUEST, serde_json::json!({"error": "bad_request"}))
+    })
+}
+
+#[tokio::test]
+async fn test_render() {
+    let build = || {
+        let mut fuse = Fuse::new();
+        fuse.endpoints(
+            defer,
+            vec![],
+            crate::fuse_endpoints!("GET: /hello/{name}" => hello_page, "GET: /broken" => broken_page, "GET: /draft" => render_then_fail),
+        );
+        fuse.router
+    };
+
+    let req = Request::builder().uri("/hello/%3Cb%3E").body(Body::empty()).unwrap();
+    let res = build().oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["content-type"], "text/html; charset=utf-8");
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(bytes, "<h1>Hello &#60;b&#62;</h1>");
+
+    let req = Request::builder().uri("/broken").body(Body::empty()).unwrap();
+    let res = build().oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    assert!(String::from_utf8_lossy(&bytes).contains("failed to render template"));
+
+    // a later failure is not labelled html by an earlier render
+    let req = Request::builder().uri("/draft").body(Body::empty()).unwrap();
+    let res = build().oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(res.headers()["content-type"], "application/json");
+}
+
+fn pre_ws_user(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let Some(user) = ctx.req.headers().get("x-user").and_then(|v| v.to_str().ok()).map(|s| s.to_string()) else {
+            return ctx.err(StatusCode::UNAUTHORIZED, "no user");
+        };
+        ctx.set("user", user);
+        ctx.ok(StatusCode::OK, ())
+    })
+}
+
+fn ws_echo(mut ws: FuseWebSocket) -> BoxFuture<'static, ()> {
+    Box::pin(async move {
+        let user = ws.get::<String>("user").map(|s| s.to_string()).unwrap_or_default();
+        while let Some(Ok(msg)) = ws.recv().await {
+            if let WsMessage::Text(text) = msg
+                && ws.send_text(&format!("{}:{}", user, text.as_str())).await.is_err()
+            {
+                break;
+            }
+        }
+    })
+}
+
+#[tokio::test]
+async fn test_websocket() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let build = || {
+        let mut fuse = Fuse::new();
+        fuse.ws_endpoints(defer, crate::fuse_handlers!(pre_ws_user), crate::fuse_ws_endpoints!("GET: /ws/echo" => ws_echo));
+        assert!(fuse.errors.is_empty(), "{:?}", fuse.errors);
+        fuse.into_router()
+    };
+
+    let req = Request::builder().uri("/ws/echo").body(Body::empty()).unwrap();
+    let res = build().oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, build()).await.unwrap() });
+
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let handshake = "GET /ws/echo HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+        Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\nx-user: u1\r\n\r\n";
+    stream.write_all(handshake.as_bytes()).await.unwrap();
+
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        head.push(stream.read_u8().await.unwrap());
+    }
+    assert!(String::from_utf8_lossy(&head).starts_with("HTTP/1.1 101"));
+
+    // masked client text frame carrying "hi"
+    let mask = [1u8, 2, 3, 4];
+    let mut frame = vec![0x81, 0x82];
+    frame.extend_from_slice(&mask);
+    frame.extend(b"hi".iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+    stream.write_all(&frame).await.unwrap();
+
+    let mut reply = [0u8; 7];
+    stream.read_exact(&mut reply).await.unwrap();
+    assert_eq!(&reply[..2], &[0x81, 5]);
+    assert_eq!(&reply[2..], b"u1:hi");
+
+    let mut fuse = Fuse::new();
+    fuse.ws_endpoints(defer, vec![], crate::fuse_ws_endpoints!("POST: /ws/echo" => ws_echo));
+    assert_eq!(fuse.errors.len(), 1);
+}
+
+fn ws_boom(_ws: FuseWebSocket) -> BoxFuture<'static, ()> {
+    Box::pin(async move { panic!("ws boom") })
+}
+
+#[tokio::test]
+async fn test_websocket_panic() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut fuse = Fuse::new();
+    fuse.ws_endpoints(defer, vec![], crate::fuse_ws_endpoints!("GET: /ws/boom" => ws_boom));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, fuse.into_router()).await.unwrap() });
+
+    // the panic ends only its own connection
+    for _ in 0..2 {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let handshake = "GET /ws/boom HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+            Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n";
+        stream.write_all(handshake.as_bytes()).await.unwrap();
+        let mut rest = Vec::new();
+        stream.read_to_end(&mut rest).await.unwrap();
+        assert!(String::from_utf8_lossy(&rest).starts_with("HTTP/1.1 101"));
+    }
+
+    let stats = fuse_ws::WsStats::default();
+    let panic = fuse_panic::catch(ws_boom_handler()).await.err();
+    let payload = fuse_ws::disconnect_payload("GET: /ws/boom", "/ws/boom", &stats, panic.as_ref());
+    assert_eq!(payload["panicked"], true);
+    assert_eq!(payload["panic"]["message"], "ws boom");
+    assert!(payload["location"].as_str().unwrap().contains("fuse.rs"));
+    assert_eq!(fuse_ws::disconnect_payload("GET: /ws/boom", "/ws/boom", &stats, None)["panicked"], false);
+}
+
+async fn ws_boom_handler() {
+    panic!("ws boom")
+}
+
+fn progress(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let events = futures_util::stream::iter([25, 100])
+            .map(|pct| SseEvent::default().id(pct.to_string()).event("progress").data(pct.to_string()).retry(Duration::from_secs(3)));
+        ctx.ok_sse(events)
+    })
+}
+
+#[tokio::test]
+async fn test_sse() {
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /jobs/{id}/progress" => progress));
+
+    let req = Request::builder().uri("/jobs/1/progress").body(Body::empty()).unwrap();
+    let res = fuse.router.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["content-type"], "text/event-stream");
+    assert!(res.extensions().get::<Arc<r_context_sse::SseStats>>().is_some());
+
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let body = String::from_utf8_lossy(&bytes);
+    assert!(body.contains("id: 25\nevent: progress\ndata: 25\nretry: 3000\n\n"), "{}", body);
+    assert!(body.contains("data: 100\n"));
+}
+
+fn custom_health(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move { ctx.ok(StatusCode::OK, "custom") })
+}
+
+/// Serializes the tests that read or flip the process-wide shutdown flag.
+static SHUTDOWN_STATE: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+#[tokio::test]
+async fn test_readyz_during_drain() {
+    let _guard = SHUTDOWN_STATE.lock().await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /healthz" => custom_health));
+    tokio::spawn(serve_with(listener, fuse.into_router()));
+
+    let drain = tokio::spawn(crate::util::lifecycle::drain(Duration::from_millis(500)));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // fresh connections are still accepted, only readiness fails
+    let client = reqwest::Client::builder().pool_max_idle_per_host(0).build().unwrap();
+    let res = client.get(format!("http://{}/readyz", addr)).send().await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(res.text().await.unwrap(), "{\"status\":\"shutting_down\"}");
+    let res = client.get(format!("http://{}/healthz", addr)).send().await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+
+    drain.await.unwrap();
+    crate::util::lifecycle::reset_shutting_down();
+}
+
+#[tokio::test]
+async fn test_health_routes() {
+    let _guard = SHUTDOWN_STATE.lock().await;
+    for (uri, expected) in [("/healthz", "custom"), ("/livez", "{\"status\":\"ok\"}"), ("/readyz", "{\"status\":\"ready\",\"checks\":{}}")]
+    {
+        let mut fuse = Fuse::new();
+        fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /healthz" => custom_health));
+        let req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let res = fuse.into_router().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK, "{}", uri);
+        let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(bytes, expected, "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn test_metrics_route() {
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /test/metrics" => custom_health));
+    let router = fuse.into_router();
+
+    let req = Request::builder().uri("/test/metrics").body(Body::empty()).unwrap();
+    assert_eq!(router.clone().oneshot(req).await.unwrap().status(), StatusCode::OK);
+
+    let req = Request::builder().uri("/metrics").body(Body::empty()).unwrap();
+    let res = router.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["content-type"], "text/plain; version=0.0.4");
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let body = String::from_utf8_lossy(&bytes);
+    assert!(body.contains(r#"rmod_fuse_request_duration_seconds_count{endpoint="GET: /test/metrics",status="200"}"#), "{}", body);
+}
+
+#[tokio::test]
+async fn test_rate_limit() {
+    let mut fuse = Fuse::new();
+    fuse.option("GET: /quote", opt().rate_limit(rate_limit(2, Duration::from_secs(60)).by_header("x-api-key")));
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /quote" => custom_health));
+    let router = fuse.into_router();
+
+    let send = |key: &'static str| {
+        let req = Request::builder().uri("/quote").header("x-api-key", key).body(Body::empty()).unwrap();
+        router.clone().oneshot(req)
+    };
+    assert_eq!(send("a").await.unwrap().status(), StatusCode::OK);
+    assert_eq!(send("a").await.unwrap().status(), StatusCode::OK);
+
+    let res = send("a").await.unwrap();
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(res.headers()[header::RETRY_AFTER], "30");
+    assert_eq!(res.headers()[header::CONTENT_TYPE], "application/problem+json");
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let problem: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(
+        (problem["status"].as_u64(), &problem["code"], &problem["detail"]),
+        (Some(429), &"too_many_requests".into(), &"rate limit exceeded".into())
+    );
+
+    assert_eq!(send("b").await.unwrap().status(), StatusCode::OK);
+
+    let mut fuse = Fuse::new();
+    fuse.option("GET: /quote", opt().rate_limit(rate_limit(0, Duration::from_secs(1))));
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /quote" => custom_health));
+    assert_eq!(fuse.errors, vec!["endpoint 'GET: /quote': rate limit capacity must be greater than 0"]);
+}
+
+fn slow(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        ctx.ok(StatusCode::OK, "done")
+    })
+}
+
+fn defer_timeout(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        if ctx.is_timed_out() {
+            ctx.set_header("x-timed-out", "1");
+        }
+        defer(ctx).await
+    })
+}
+
+#[tokio::test]
+async fn test_timeout() {
+    let mut fuse = Fuse::new();
+    fuse.option("GET: /slow", opt().timeout(Duration::from_millis(20)));
+    fuse.option("GET: /slow/unbounded", opt().timeout(Duration::ZERO));
+    fuse.endpoints(defer_timeout, vec![], crate::fuse_endpoints!("GET: /slow" => slow, "GET: /slow/unbounded" => slow));
+    let router = fuse.into_router();
+
+    let req = Request::builder().uri("/slow").body(Body::empty()).unwrap();
+    let res = router.clone().oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::GATEWAY_TIMEOUT);
+    assert_eq!(res.headers()["x-timed-out"], "1");
+    assert_eq!(res.headers()[header::CONTENT_TYPE], "application/problem+json");
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let problem: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(
+        (problem["status"].as_u64(), &problem["code"], &problem["detail"]),
+        (Some(504), &"gateway_timeout".into(), &"request timed out".into())
+    );
+
+    let req = Request::builder().uri("/slow/unbounded").body(Body::empty()).unwrap();
+    let res = router.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res.headers().get("x-timed-out").is_none());
+}
+
+fn boom(_ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move { panic!("boom") })
+}
+
+fn defer_panic(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        if ctx.is_panicked() {
+            ctx.set_header("x-panicked", "1");
+        }
+        if ctx.req.uri().path() == "/boom/defer" {
+            panic!("defer boom");
+        }
+        defer(ctx).await
+    })
+}
+
+#[tokio::test]
+async fn test_panic_recovery() {
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer_panic, vec![], crate::fuse_endpoints!("GET: /boom" => boom, "GET: /boom/defer" => custom_health));
+    let router = fuse.into_router();
+
+    let req = Request::builder().uri("/boom").body(Body::empty()).unwrap();
+    let res = router.clone().oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(res.headers()["x-panicked"], "1");
+    assert_eq!(res.headers()[header::CONTENT_TYPE], "application/problem+json");
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let problem: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(
+        (problem["status"].as_u64(), &problem["code"], &problem["detail"]),
+        (Some(500), &"internal_server_error".into(), &"handler panicked".into())
+    );
+
+    let req = Request::builder().uri("/boom/defer").body(Body::empty()).unwrap();
+    let res = router.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let mut ctx = FuseRContext::new(Request::new(Body::empty()));
+    let res = ctx.res_handle(Arc::new(vec![]), defer, Arc::new(vec![boom as FuseHandler]), "GET: /boom").await;
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let (panic, source) = ctx.panicked.as_ref().unwrap();
+    assert_eq!(panic.message, "boom");
+    assert!(panic.location.as_deref().unwrap().contains("test/fuse.rs"));
+    assert_eq!((source.name, source.handler_index), ("handler", 0));
+}
+
+#[derive(crate::validate::Validate, serde::Deserialize)]
+struct Transfer {
+    #[validate(length(min = 1, max = 16))]
+    account: String,
+    #[validate(range(min = 1))]
+    amount: i64,
+}
+
+fn transfer(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let body = ctx.json_valid::<Transfer>()?;
+        ctx.ok(StatusCode::OK, format!("{}:{}", body.account, body.amount))
+    })
+}
+
+#[tokio::test]
+async fn test_json_valid() {
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("POST: /transfer" => transfer));
+    assert_eq!(call(fuse, "POST", "/transfer", r#"{"account":"a-1","amount":5}"#).await, (StatusCode::OK, "a-1:5".to_string()));
+
+    let cases = [
+        (
+            r#"{"account":"","amount":0}"#,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "validation_failed",
+            "request body is invalid",
+            serde_json::json!({ "fields": [
+                { "path": "account", "code": "length", "message": "length must be between 1 and 16" },
+                { "path": "amount", "code": "range", "message": "value must be at least 1" },
+            ] }),
+        ),
+        (
+            r#"{"account":"a-1","amount":"5"}"#,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "validation_failed",
+            "request body is invalid",
+            serde_json::json!({ "fields": [{ "path": "amount", "code": "invalid", "message": "invalid type: string \"5\", expected i64" }] }),
+        ),
+        (r#"{"account":"#, StatusCode::BAD_REQUEST, "invalid_json", "EOF while parsing a value", serde_json::Value::Null),
+    ];
+    for (body, status, code, detail, details) in cases {
+        let mut fuse = Fuse::new();
+        fuse.endpoints(defer, vec![], crate::fuse_endpoints!("POST: /transfer" => transfer));
+        let (res_status, res_body) = call(fuse, "POST", "/transfer", body).await;
+        assert_eq!(res_status, status, "{}", body);
+        let problem: serde_json::Value = serde_json::from_str(&res_body).unwrap();
+        assert_eq!((&problem["code"], &problem["detail"], &problem["details"]), (&code.into(), &detail.into(), &details), "{}", body);
+    }
+}
+
+fn find_user(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        if ctx.req.uri().path() == "/users/db" {
+            let res: Result<(), sqlx::Error> = Err(sqlx::Error::RowNotFound);
+            res.or_fail(ctx)?;
+        }
+        ctx.fail(FuseError::not_found("user not found").with_details(serde_json::json!({ "user_id": 7 })))
+    })
+}
+
+#[tokio::test]
+async fn test_problem_details() {
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /users/{id}" => find_user));
+    let router = fuse.into_router();
+
+    let req = Request::builder().uri("/users/7").header("x-trace-id", "trace-1").body(Body::empty()).unwrap();
+    let res = router.clone().oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    assert_eq!(res.headers()[header::CONTENT_TYPE], "application/problem+json");
+    assert_eq!(res.headers()["x-trace-id"], "trace-1");
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(
+        bytes,
+        r#"{"type":"about:blank","title":"Not Found","status":404,"detail":"user not found","code":"not_found","trace_id":"trace-1","details":{"user_id":7}}"#
+    );
+
+    let req = Request::builder().uri("/users/db").header("x-trace-id", "trace-2").body(Body::empty()).unwrap();
+    let res = router.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(
+        bytes,
+        r#"{"type":"about:blank","title":"Not Found","status":404,"detail":"record not found","code":"not_found","trace_id":"trace-2"}"#
+    );
+
+    let status = tonic::Status::from(FuseError::lock_unavailable("lock wait timed out"));
+    assert_eq!(status.code(), tonic::Code::Aborted);
+    assert_eq!(status.message(), "resource is busy, retry later");
+    assert_eq!(status.metadata().get("x-error-code").unwrap(), "lock_unavailable");
+
+    let status = tonic::Status::from(FuseError::not_found("user not found").with_details(serde_json::json!({"user_id": 7})));
+    let details = <fuse_error::RpcStatus as prost::Message>::decode(status.details()).unwrap();
+    assert_eq!(details.code, tonic::Code::NotFound as i32);
+    assert_eq!(details.message, "user not found");
+    assert_eq!(details.details[0].type_url, "type.googleapis.com/google.rpc.ErrorInfo");
+    let info = <fuse_error::RpcErrorInfo as prost::Message>::decode(details.details[0].value.as_slice()).unwrap();
+    assert_eq!(info.reason, "not_found");
+    assert_eq!(info.metadata["details"], r#"{"user_id":7}"#);
+}
+
+/// An order placed by a partner.
+#[derive(serde::Deserialize, FuseSchema)]
+#[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
+struct DocOrder {
+    /// Reference of the partner.
+    client_ref: String,
+    side: DocSide,
+    price: Option<rust_decimal::Decimal>,
+    #[serde(default)]
+    notes: Vec<String>,
+}
+
+#[derive(serde::Deserialize, FuseSchema)]
+#[serde(rename_all = "snake_case")]
+#[allow(dead_code)]
+enum DocSide {
+    Buy,
+    StopLoss,
+}
+
+#[crate::fuse_handler(summary = "Place an order", tag = "orders", request = DocOrder, response = DocOrder, status = 201)]
+async fn place_order(ctx: &mut FuseRContext) -> FuseResult {
+    ctx.ok(StatusCode::CREATED, "placed")
+}
+
+static EAGER_CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// Hand-written handler with work before the future, which must not run when the docs are collected.
+fn eager_handler(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    EAGER_CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    Box::pin(async move { ctx.ok(StatusCode::OK, ()) })
+}
+
+#[tokio::test]
+async fn test_openapi_document() {
+    let mut fuse = Fuse::new();
+    fuse.api_info("Partner API", "2.1.0");
+    fuse.option("POST: /orders/{uid}", opt().doc(PLACE_ORDER_DOC));
+    fuse.endpoints(
+        defer,
+        vec![],
+        crate::fuse_endpoints!(
+            "POST: /orders/{uid}" => place_order,
+            "POST: /orders-draft" => place_order,
+            "GET: /files/{*path}" => custom_health,
+            "GET: /eager" => eager_handler,
+        ),
+    );
+    let router = fuse.into_router();
+    assert_eq!(EAGER_CALLS.load(std::sync::atomic::Ordering::SeqCst), 0, "handlers are not called to collect docs");
+
+    let req = Request::builder().method("POST").uri("/orders/abc").body(Body::empty()).unwrap();
+    assert_eq!(router.clone().oneshot(req).await.unwrap().status(), StatusCode::CREATED);
+
+    let req = Request::builder().uri("/openapi.json").body(Body::empty()).unwrap();
+    let res = router.clone().oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let doc: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+
+    assert_eq!(doc["openapi"], "3.0.3");
+    assert_eq!(doc["info"], serde_json::json!({ "title": "Partner API", "version": "2.1.0" }));
+
+    let op = &doc["paths"]["/orders/{uid}"]["post"];
+    assert_eq!(op["summary"], "Place an order");
+    assert_eq!(op["tags"], serde_json::json!(["orders"]));
+    assert_eq!(op["parameters"][0]["name"], "uid");
+    assert_eq!(op["requestBody"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/DocOrder");
+    assert_eq!(op["responses"]["201"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/DocOrder");
+
+    assert!(doc["paths"]["/orders-draft"]["post"]["summary"].is_null(), "docs only go where they are attached");
+
+    let undocumented = &doc["paths"]["/files/{path}"]["get"];
+    assert_eq!(undocumented["parameters"][0]["name"], "path");
+    assert_eq!(undocumented["responses"]["200"]["description"], "OK");
+
+    let order = &doc["components"]["schemas"]["DocOrder"];
+    assert_eq!(order["description"], "An order placed by a partner.");
+    assert_eq!(order["required"], serde_json::json!(["clientRef", "side"]));
+    assert_eq!(order["properties"]["clientRef"], serde_json::json!({ "type": "string", "description": "Reference of the partner." }));
+    assert_eq!(order["properties"]["price"], serde_json::json!({ "type": "string", "format": "decimal", "nullable": true }));
+    assert_eq!(order["properties"]["notes"]["items"]["type"], "string");
+    assert_eq!(doc["components"]["schemas"]["DocSide"]["enum"], serde_json::json!(["buy", "stop_loss"]));
+
+    let req = Request::builder().uri("/docs").body(Body::empty()).unwrap();
+    let res = router.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res.headers()["content-type"].to_str().unwrap().starts_with("text/html"));
+}
+
+fn client_ip_handler(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let ip = ctx.client_ip();
+        ctx.ok(StatusCode::OK, ip)
+    })
+}
+
+#[tokio::test]
+async fn test_tls() {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/src/fuse/test/tls");
+    let source = fuse_tls::TlsSource::new(&crate::config::TlsConfig {
+        cert: format!("{}/server.pem", dir),
+        key: std::fs::read_to_string(format!("{}/server.key", dir)).unwrap(),
+        client_ca: Some(format!("{}/ca.pem", dir)),
+        client_cert_optional: false,
+    });
+
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /ip" => client_ip_handler));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(serve_with(fuse_tls::TlsListener::new(listener, source).unwrap(), fuse.into_router()));
+
+    let ca = reqwest::Certificate::from_pem(&std::fs::read(format!("{}/ca.pem", dir)).unwrap()).unwrap();
+    let mut identity = std::fs::read(format!("{}/client.pem", dir)).unwrap();
+    identity.extend(std::fs::read(format!("{}/client.key", dir)).unwrap());
+    let url = format!("https://localhost:{}/ip", port);
+
+    let client =
+        reqwest::Client::builder().tls_certs_only([ca.clone()]).identity(reqwest::Identity::from_pem(&identity).unwrap()).build().unwrap();
+    let res = client.get(&url).send().await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    assert_eq!(res.version(), reqwest::Version::HTTP_2);
+    assert_eq!(res.text().await.unwrap(), "127.0.0.1");
+
+    // mTLS: a client without a certificate is turned away
+    let client = reqwest::Client::builder().tls_certs_only([ca]).build().unwrap();
+    assert!(client.get(&url).send().await.is_err());
+}
+
+#[derive(Clone)]
+struct EchoService;
+
+impl tonic::server::NamedService for EchoService {
+    const NAME: &'static str = "test.Echo";
+}
+
+impl tower::Service<http::Request<tonic::body::BoxBody>> for EchoService {
+    type Response = http::Response<tonic::body::BoxBody>;
+    type Error = std::convert::Infallible;
+    type Future = std::future::Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _: http::Request<tonic::body::BoxBody>) -> Self::Future {
+        std::future::ready(Ok(tonic::Status::unimplemented("echo").into_http()))
+    }
+}
+
+#[tokio::test]
+async fn test_rest_grpc_single_port() {
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, vec![], crate::fuse_endpoints!("GET: /ip" => client_ip_handler));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mux = fuse_mux::FuseMux { rest: fuse.into_router(), grpc: crate::fuse::grpc_routes(EchoService).await, local_addr: Some(addr) };
+    tokio::spawn(serve_with(listener, mux));
+
+    let res = reqwest::get(format!("http://{}/ip", addr)).await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    assert_eq!(res.text().await.unwrap(), "127.0.0.1");
+
+    let channel = tonic::transport::Endpoint::from_shared(format!("http://{}", addr)).unwrap().connect().await.unwrap();
+    let mut health = tonic_health::pb::health_client::HealthClient::new(channel);
+    for service in ["", "test.Echo"] {
+        let req = tonic_health::pb::HealthCheckRequest { service: service.to_string() };
+        let res = health.check(req).await.unwrap().into_inner();
+        assert_eq!(res.status(), tonic_health::pb::health_check_response::ServingStatus::Serving);
+    }
+}
+
+#[tokio::test]
+async fn test_idempotency_stored_response() {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, "application/json".parse().unwrap());
+    headers.insert(header::CONTENT_LENGTH, "11".parse().unwrap());
+    headers.insert("x-payment-id", "p-1".parse().unwrap());
+    let stored = fuse_idempotency::StoredResponse::new("fp", StatusCode::CREATED, &headers, b"{\"ok\":true}");
+
+    let stored: fuse_idempotency::StoredResponse = serde_json::from_str(&serde_json::to_string(&stored).unwrap()).unwrap();
+    let res = stored.into_response();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    assert_eq!(res.headers()["x-payment-id"], "p-1");
+    assert_eq!(res.headers()["idempotent-replayed"], "true");
+    assert!(!res.headers().contains_key(header::DATE));
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&bytes[..], b"{\"ok\":true}");
+}
+
+static PAYMENTS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+fn pay(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let n = PAYMENTS.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+        ctx.ok(StatusCode::CREATED, format!("payment {}", n))
+    })
+}
+
+fn pre_partner_key(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let Some(partner) = ctx.req.headers().get("x-partner").and_then(|v| v.to_str().ok()).map(|s| s.to_string()) else {
+            return ctx.fail(FuseError::unauthorized("unknown partner"));
+        };
+        ctx.set_partner_uid(partner);
+        ctx.ok(StatusCode::OK, ())
+    })
+}
+
+fn pay_stream(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let n = PAYMENTS.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+        let chunks = vec![Ok::<_, std::io::Error>(axum::body::Bytes::from(format!("payment {}", n)))];
+        ctx.ok_stream(StatusCode::CREATED, "text/plain", futures_util::stream::iter(chunks))
+    })
+}
+
+/// Runtime of the Postgres idempotency test. The dist lock pool is process-wide and its connections are driven by
+/// the runtime that opened them, so it is opened on a runtime that outlives the test for the lock tests to use.
+fn pg_runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: std::sync::OnceLock<tokio::runtime::Runtime> = std::sync::OnceLock::new();
+    RUNTIME.get_or_init(|| tokio::runtime::Builder::new_multi_thread().enable_all().build().expect("failed to build test runtime"))
+}
+
+#[test]
+fn test_idempotency_pg() {
+    pg_runtime().block_on(idempotency_pg());
+}
+
+async fn idempotency_pg() {
+    if std::net::TcpStream::connect("127.0.0.1:15432").is_err() {
+        println!("Postgres container is not running on port 15432. Skipping test_idempotency_pg.");
+        return;
+    }
+
+    let db = || crate::config::DbConfig {
+        host: "127.0.0.1".to_string(),
+        port: 15432,
+        database: "dist-lock-db".to_string(), // from res/compose/.env
+        schema: Some("public".to_string()),
+        username: "rmod".to_string(),
+        password: "E5BEWREN1N7w12G9U73JKPf8rQst4WQPMHKLqdNdG1gGabPQi9".to_string(),
+        max_connections: 5,
+        min_connections: 1,
+        acquire_timeout: Some(30),
+        idle_timeout: Some(10),
+        lock_timeout: Some(30),
+    };
+    if let Err(e) = crate::config::pg_lock(&db()).await {
+        println!("Init info: {}", e);
+    }
+    crate::config::db_setup("idempotency", db(), None, 0, "", "").await.unwrap();
+    crate::config::idempotency_db("idempotency").await.unwrap();
+
+    let mut fuse = Fuse::new();
+    fuse.option("POST: /pay", opt().idempotency(idempotency(Duration::from_secs(60))));
+    fuse.option("POST: /pay-stream", opt().idempotency(idempotency(Duration::from_secs(60))));
+    fuse.endpoints(
+        defer,
+        crate::fuse_handlers!(pre_partner_key),
+        crate::fuse_endpoints!("POST: /pay" => pay, "POST: /pay-stream" => pay_stream),
+    );
+    assert!(fuse.errors.is_empty(), "{:?}", fuse.errors);
+    let router = fuse.into_router();
+
+    let key = crate::uid::new();
+    let send_as = |uri: &'static str, partner: Option<&str>, key: Option<&str>, body: &'static str| {
+        let mut req = Request::builder().method("POST").uri(uri);
+        if let Some(partner) = partner {
+            req = req.header("x-partner", partner);
+        }
+        if let Some(key) = key {
+            req = req.header("idempotency-key", key);
+        }
+        router.clone().oneshot(req.body(Body::from(body)).unwrap())
+    };
+    let send = |key: Option<&str>, body: &'static str| send_as("/pay", Some("partner-a"), key, body);
+    let text = |res: Response| async move {
+        let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8_lossy(&bytes).to_string()
+    };
+
+    let first = send(Some(&key), "{\"amount\":10}").await.unwrap();
+    assert_eq!(first.status(), StatusCode::CREATED);
+    assert!(!first.headers().contains_key("idempotent-replayed"));
+    let first = text(first).await;
+
+    let repeat = send(Some(&key), "{\"amount\":10}").await.unwrap();
+    assert_eq!(repeat.status(), StatusCode::CREATED);
+    assert_eq!(repeat.headers()["idempotent-replayed"], "true");
+    assert_eq!(text(repeat).await, first);
+
+    let reused = send(Some(&key), "{\"amount\":99}").await.unwrap();
+    assert_eq!(reused.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(reused.headers()[header::CONTENT_TYPE], "application/problem+json");
+    let problem: serde_json::Value = serde_json::from_str(&text(reused).await).unwrap();
+    assert_eq!(problem["code"], "idempotency_key_reused");
+
+    let unkeyed = send(None, "{\"amount\":10}").await.unwrap();
+    assert_ne!(text(unkeyed).await, first);
+
+    // the preconditions run before a replay, and the key is scoped to the partner they set
+    let anonymous = send_as("/pay", None, Some(&key), "{\"amount\":10}").await.unwrap();
+    assert_eq!(anonymous.status(), StatusCode::UNAUTHORIZED);
+    let other = send_as("/pay", Some("partner-b"), Some(&key), "{\"amount\":99}").await.unwrap();
+    assert_eq!(other.status(), StatusCode::CREATED);
+    assert!(!other.headers().contains_key("idempotent-replayed"));
+
+    // streamed bodies are passed through and not stored
+    let stream_key = crate::uid::new();
+    let streamed = send_as("/pay-stream", Some("partner-a"), Some(&stream_key), "{}").await.unwrap();
+    let streamed = text(streamed).await;
+    assert!(streamed.starts_with("payment "), "{}", streamed);
+    let again = send_as("/pay-stream", Some("partner-a"), Some(&stream_key), "{}").await.unwrap();
+    assert!(!again.headers().contains_key("idempotent-replayed"));
+    assert_ne!(text(again).await, streamed);
+}
+
+fn whoami(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let sub = ctx.jwt_claims().and_then(|c| c.sub().map(str::to_string)).unwrap_or_default();
+        let user_uid = crate::clog::get_current_ctx().and_then(|c| c.user_uid).unwrap_or_default();
+        ctx.ok(StatusCode::OK, format!("{}:{}", sub, user_uid))
+    })
+}
+
+#[tokio::test]
+async fn test_jwt_auth() {
+    let _ = crate::config::jwt_auth(&crate::config::JwtConfig {
+        secrets: vec!["jwt-secret".to_string()],
+        issuer: Some("rmod".to_string()),
+        audience: None,
+        leeway: None,
+    });
+    let token = crate::util::jwt::encode("u-7".to_string(), "rmod".to_string(), "jwt-secret", crate::time::to_delta("1m"));
+    let forged = crate::util::jwt::encode("u-7".to_string(), "rmod".to_string(), "other", crate::time::to_delta("1m"));
+
+    let mut fuse = Fuse::new();
+    fuse.endpoints(defer, crate::fuse_handlers!(jwt_auth), crate::fuse_endpoints!("GET: /me" => whoami));
+    let send = |authorization: Option<String>| {
+        let mut req = Request::builder().method("GET").uri("/me");
+        if let Some(v) = authorization {
+            req = req.header(header::AUTHORIZATION, v);
+        }
+        fuse.router.clone().oneshot(req.body(Body::empty()).unwrap())
+    };
+
+    let res = send(Some(format!("Bearer {}", token))).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&bytes[..], b"u-7:u-7");
+
+    let res = send(None).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(res.headers()[header::WWW_AUTHENTICATE], "Bearer");
+
+    let res = send(Some(format!("Bearer {}", forged))).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(res.headers()[header::WWW_AUTHENTICATE], "Bearer error=\"invalid_token\"");
+
+    let mut req = tonic::Request::new(());
+    req.metadata_mut().insert("authorization", format!("Bearer {}", token).parse().unwrap());
+    let req = jwt_interceptor(req).unwrap();
+    assert_eq!(req.extensions().get::<crate::util::jwt::VerifiedClaims>().unwrap().sub(), Some("u-7"));
+
+    let mut req = tonic::Request::new(());
+    req.metadata_mut().insert("authorization", format!("Bearer {}", forged).parse().unwrap());
+    assert_eq!(jwt_interceptor(req).unwrap_err().code(), tonic::Code::Unauthenticated);
+}
+
+fn sign_webhook(secret: &str, timestamp: &str, body: &str) -> String {
+    use hmac::Mac;
+    let mut mac = hmac::Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+    mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn received(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        let body = ctx.body.as_deref().map(|b| String::from_utf8_lossy(b).to_string()).unwrap_or_default();
+        ctx.ok(StatusCode::OK, body)
+    })
+}
+
+#[tokio::test]
+async fn test_webhook_signature() {
+    crate::config::webhook(
+        "partner-test",
+        &crate::config::WebhookConfig {
+            secrets: vec!["new".to_string(), "old".to_string()],
+            signature_header: "x-signature".to_string(),
+            timestamp_header: "x-timestamp".to_string(),
+            nonce_header: None,
+            message: crate::config::WebhookMessage::TimestampDotBody,
+            tolerance: Some(60),
+        },
+    )
+    .unwrap();
+
+    let mut fuse = Fuse::new();
+    fuse.endpoints(
+        defer,
+        crate::fuse_handlers!(crate::fuse_webhook!("partner-test")),
+        crate::fuse_endpoints!("POST: /callback" => received),
+    );
+    let send = |timestamp: String, signature: String, body: &'static str| {
+        let req = Request::builder().method("POST").uri("/callback").header("x-timestamp", timestamp).header("x-signature", signature);
+        fuse.router.clone().oneshot(req.body(Body::from(body)).unwrap())
+    };
+    let now = chrono::Utc::now().timestamp().to_string();
+    let body = r#"{"event":"paid"}"#;
+
+    let res = send(now.clone(), format!("sha256={}", sign_webhook("old", &now, body)), body).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&bytes[..], body.as_bytes());
+
+    // the same delivery again is a replay, however its signature is written
+    let signature = sign_webhook("old", &now, body);
+    let bytes: Vec<u8> = (0..signature.len()).step_by(2).map(|i| u8::from_str_radix(&signature[i..i + 2], 16).unwrap()).collect();
+    for resent in [
+        format!("sha256={}", signature),
+        signature.clone(),
+        base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &bytes),
+        format!("{}, x", signature),
+    ] {
+        let res = send(now.clone(), resent.clone(), body).await.unwrap();
+        assert_eq!(res.status(), StatusCode::CONFLICT, "{}", resent);
+    }
+
+    let other = r#"{"event":"shipped"}"#;
+    let res = send(now.clone(), format!("v0,{}", sign_webhook("new", &now, other)), other).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let stale = (chrono::Utc::now().timestamp() - 120).to_string();
+    let res = send(stale.clone(), sign_webhook("new", &stale, body), body).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let res = send(now.clone(), sign_webhook("retired", &now, body), body).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let res = send(now.clone(), sign_webhook("new", &now, body), r#"{"event":"refunded"}"#).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    assert!(
+        crate::config::webhook(
+            "partner-test",
+            &crate::config::WebhookConfig {
+                secrets: vec!["new".to_string()],
+                signature_header: "x-signature".to_string(),
+                timestamp_header: "x-timestamp".to_string(),
+                nonce_header: None,
+                message: crate::config::WebhookMessage::BodyTimestamp,
+                tolerance: None,
+            }
+        )
+        .is_err()
+    );
+}
+
+static FLAKY_DELIVERIES: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// Fails the first delivery with 500 and panics on the second, the third goes through.
+fn flaky_delivery(ctx: &mut FuseRContext) -> BoxFuture<'_, FuseResult> {
+    Box::pin(async move {
+        match FLAKY_DELIVERIES.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+            0 => ctx.fail(FuseError::internal("downstream unavailable")),
+            1 => panic!("delivery handler panicked"),
+            _ => ctx.ok(StatusCode::OK, "stored"),
+        }
+    })
+}
+
+#[tokio::test]
+async fn test_webhook_retry_after_failure() {
+    crate::config::webhook(
+        "partner-test-retry",
+        &crate::config::WebhookConfig {
+            secrets: vec!["retry".to_string()],
+            signature_header: "x-signature".to_string(),
+            timestamp_header: "x-timestamp".to_string(),
+            nonce_header: Some("x-nonce".to_string()),
+            message: crate::config::WebhookMessage::TimestampDotBody,
+            tolerance: Some(60),
+        },
+    )
+    .unwrap();
+
+    let mut fuse = Fuse::new();
+    fuse.endpoints(
+        defer,
+        crate::fuse_handlers!(crate::fuse_webhook!("partner-test-retry")),
+        crate::fuse_endpoints!("POST: /delivery" => flaky_delivery),
+    );
+    let send = |timestamp: &str, nonce: &str, body: &'static str| {
+        let req = Request::builder()
+            .method("POST")
+            .uri("/delivery")
+            .header("x-timestamp", timestamp)
+            .header("x-nonce", nonce)
+            .header("x-signature", sign_webhook("retry", timestamp, body));
+        fuse.router.clone().oneshot(req.body(Body::from(body)).unwrap())
+    };
+    let now = chrono::Utc::now().timestamp().to_string();
+    let body = r#"{"event":"paid"}"#;
+
+    // a failed or panicked delivery frees its keys, so the partner's retry of it is processed
+    assert_eq!(send(&now, "n-1", body).await.unwrap().status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(send(&now, "n-1", body).await.unwrap().status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(send(&now, "n-1", body).await.unwrap().status(), StatusCode::OK);
+
+    // once delivered, the content and the nonce are each a replay on their own
+    assert_eq!(send(&now, "n-1", body).await.unwrap().status(), StatusCode::CONFLICT);
+    assert_eq!(send(&now, "n-2", body).await.unwrap().status(), StatusCode::CONFLICT);
+    assert_eq!(send(&now, "n-1", r#"{"event":"shipped"}"#).await.unwrap().status(), StatusCode::CONFLICT);
+
+    // a rejected replay does not hold the new nonce it came with
+    assert_eq!(send(&now, "n-2", r#"{"event":"shipped"}"#).await.unwrap().status(), StatusCode::OK);
+    assert_eq!(FLAKY_DELIVERIES.load(std::sync::atomic::Ordering::SeqCst), 4);
+}
@@ -161,6 +161,7 @@ async fn request<T: Serialize>(
     match res_result {
         Ok(res) => {
             let status_code = res.status().as_u16() as i32;
+            crate::metrics::http_client(&get_domain(url), status_code, duration_ms);
             let http_res = http::Response::from(res);
             let (parts, body) = http_res.into_parts();
 
@@ -227,6 +228,7 @@ async fn request<T: Serialize>(
         }
         Err(err) => {
             let status_code = err.status().map(|s| s.as_u16() as i32).unwrap_or(500);
+            crate::metrics::http_client(&get_domain(url), status_code, duration_ms);
 
             if !is_excluded && clog_config.is_some() {
                 let req_body_val = clog::parse_body_to_json_val(&req_body_str);
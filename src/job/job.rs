@@ -106,6 +106,8 @@ async fn run_job_handler(name: String, handler: fn() -> BoxFuture<'static, ()>)
         }
     };
 
+    crate::metrics::job_run(&name, status_code, duration_ms);
+
     if !is_excluded && clog_config.is_some() {
         let mut payload_map = serde_json::json!({
             "job_name": name,
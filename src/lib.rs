@@ -8,6 +8,9 @@
  * All Rights Reserved.
  */
 
+// lets the derive macros, which expand to `::rmod::...`, be used inside this crate
+extern crate self as rmod;
+
 pub mod cache;
 pub mod clog;
 pub mod config;
@@ -20,11 +23,13 @@ pub mod grc;
 pub mod http;
 pub mod job;
 pub mod lock;
+pub mod metrics;
 pub mod store;
 pub mod time;
 pub mod types;
 pub mod uid;
 pub mod util;
+pub mod validate;
 
 pub use fct::FCT;
 pub use fuse::fuse_handler;
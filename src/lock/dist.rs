@@ -38,6 +38,18 @@ impl LockOptions {
     }
 }
 
+pub(crate) fn is_dist_initialized() -> bool {
+    LOCK_TYPE.get().is_some()
+}
+
+/// Pings the dist-lock backend; `None` when no backend has been initialized.
+pub(crate) async fn dist_ping() -> Option<Result<(), String>> {
+    match LOCK_TYPE.get()? {
+        DistLockType::Pg => Some(super::pg_lock::ping().await),
+        DistLockType::Redis => Some(super::redis_lock::ping().await),
+    }
+}
+
 pub async fn dist(key: &str, opt: Option<LockOptions>) -> Result<DistLock, String> {
     let t = LOCK_TYPE.get().ok_or("Distribution lock not initialized")?;
     let (ttl_ms, wait_ms) = match opt {
@@ -19,3 +19,4 @@ pub use dist::*;
 pub(super) use model::*;
 pub(crate) use pg_lock::initialize_dist_lock as pg_lock_initialize;
 pub(crate) use redis_lock::initialize_dist_lock as redis_lock_initialize;
+pub(crate) use redis_lock::redis_url;
@@ -173,3 +173,8 @@ pub(super) async fn dist_unlock(mut conn: sqlx::pool::PoolConnection<Postgres>,
     .to_string();
     crate::clog::log_dist_lock_pg_unlock(key, duration_ms, 200, payload_json);
 }
+
+pub(super) async fn ping() -> Result<(), String> {
+    let pool = POOL.get().ok_or("Pg lock pool not initialized")?;
+    sqlx::query("SELECT 1").execute(pool).await.map(|_| ()).map_err(|e| e.to_string())
+}
@@ -14,15 +14,18 @@ use std::{sync::OnceLock, time::Duration};
 static REDIS_CLIENT: OnceLock<redis::Client> = OnceLock::new();
 static LOCK_TTL: OnceLock<i64> = OnceLock::new();
 
-pub(crate) async fn initialize_dist_lock(config: &RedisLockConfig) -> Result<(), String> {
+pub(crate) fn redis_url(config: &RedisLockConfig) -> String {
     let auth = if let Some(pass) = &config.password {
         if let Some(user) = &config.username { format!("{}:{}@", user, pass) } else { format!(":{}@", pass) }
     } else {
         "".to_string()
     };
 
-    let url = format!("redis://{}{}:{}/{}", auth, config.host, config.port, config.database);
-    let client = redis::Client::open(url).map_err(|e| e.to_string())?;
+    format!("redis://{}{}:{}/{}", auth, config.host, config.port, config.database)
+}
+
+pub(crate) async fn initialize_dist_lock(config: &RedisLockConfig) -> Result<(), String> {
+    let client = redis::Client::open(redis_url(config)).map_err(|e| e.to_string())?;
     REDIS_CLIENT.set(client).map_err(|_| "Redis Client already initialized".to_string())?;
     LOCK_TTL.set(config.ttl.unwrap_or(30000)).ok();
     super::LOCK_TYPE.set(super::DistLockType::Redis).ok();
@@ -288,3 +291,9 @@ pub(super) async fn dist_unlock(key: &str, val: &str) {
         }
     }
 }
+
+pub(super) async fn ping() -> Result<(), String> {
+    let client = REDIS_CLIENT.get().ok_or("Redis lock client not initialized")?;
+    let mut conn = client.get_multiplexed_async_connection().await.map_err(|e| e.to_string())?;
+    redis::cmd("PING").query_async::<String>(&mut conn).await.map(|_| ()).map_err(|e| e.to_string())
+}
@@ -0,0 +1,119 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+#[cfg(test)]
+#[path = "test/metrics.rs"]
+mod test;
+
+use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder};
+use std::sync::OnceLock;
+
+pub use prometheus;
+
+struct Metrics {
+    registry: Registry,
+    fuse_request: HistogramVec,
+    grpc_server: HistogramVec,
+    grpc_client: HistogramVec,
+    http_client: HistogramVec,
+    db_query: HistogramVec,
+    dist_lock_wait: HistogramVec,
+    job_run: HistogramVec,
+    job_failures: IntCounterVec,
+    cache_lookup: IntCounterVec,
+    clog_dropped: IntCounterVec,
+}
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+fn histogram(registry: &Registry, name: &str, help: &str, labels: &[&str]) -> HistogramVec {
+    let h = HistogramVec::new(HistogramOpts::new(name, help), labels).unwrap_or_else(|e| panic!("invalid histogram {}: {}", name, e));
+    registry.register(Box::new(h.clone())).unwrap_or_else(|e| panic!("failed to register {}: {}", name, e));
+    h
+}
+
+fn counter(registry: &Registry, name: &str, help: &str, labels: &[&str]) -> IntCounterVec {
+    let c = IntCounterVec::new(Opts::new(name, help), labels).unwrap_or_else(|e| panic!("invalid counter {}: {}", name, e));
+    registry.register(Box::new(c.clone())).unwrap_or_else(|e| panic!("failed to register {}: {}", name, e));
+    c
+}
+
+fn get_metrics() -> &'static Metrics {
+    METRICS.get_or_init(|| {
+        let r = Registry::new();
+        Metrics {
+            fuse_request: histogram(&r, "rmod_fuse_request_duration_seconds", "REST request duration", &["endpoint", "status"]),
+            grpc_server: histogram(&r, "rmod_grpc_server_duration_seconds", "gRPC server call duration", &["method", "status"]),
+            grpc_client: histogram(&r, "rmod_grpc_client_duration_seconds", "gRPC client call duration", &["method", "status"]),
+            http_client: histogram(&r, "rmod_http_client_duration_seconds", "Outgoing http request duration", &["domain", "status"]),
+            db_query: histogram(&r, "rmod_db_query_duration_seconds", "Database query duration", &["log_type", "status"]),
+            dist_lock_wait: histogram(&r, "rmod_dist_lock_wait_seconds", "Time spent acquiring a dist lock", &["backend", "status"]),
+            job_run: histogram(&r, "rmod_job_duration_seconds", "Background job run duration", &["job", "status"]),
+            job_failures: counter(&r, "rmod_job_failures_total", "Background job runs that failed or panicked", &["job"]),
+            cache_lookup: counter(&r, "rmod_cache_lookups_total", "Cache lookups by group and result", &["group", "result"]),
+            clog_dropped: counter(&r, "rmod_clog_dropped_total", "Central log entries dropped on a full buffer", &["log_type"]),
+            registry: r,
+        }
+    })
+}
+
+/// Registry holding the rmod metrics; services can register their own collectors on it.
+pub fn registry() -> &'static Registry {
+    &get_metrics().registry
+}
+
+/// All metrics in the Prometheus text format.
+pub fn render() -> String {
+    TextEncoder::new().encode_to_string(&registry().gather()).unwrap_or_default()
+}
+
+fn secs(duration_ms: i32) -> f64 {
+    duration_ms.max(0) as f64 / 1000.0
+}
+
+pub(crate) fn fuse_request(endpoint_key: &str, status: u16, duration_ms: i32) {
+    get_metrics().fuse_request.with_label_values(&[endpoint_key, &status.to_string()]).observe(secs(duration_ms));
+}
+
+pub(crate) fn grpc_server(method: &str, grpc_status: &str, duration_ms: i32) {
+    get_metrics().grpc_server.with_label_values(&[method, grpc_status]).observe(secs(duration_ms));
+}
+
+pub(crate) fn grpc_client(method: &str, grpc_status: &str, duration_ms: i32) {
+    get_metrics().grpc_client.with_label_values(&[method, grpc_status]).observe(secs(duration_ms));
+}
+
+pub(crate) fn http_client(domain: &str, status_code: i32, duration_ms: i32) {
+    get_metrics().http_client.with_label_values(&[domain, &status_code.to_string()]).observe(secs(duration_ms));
+}
+
+pub(crate) fn db_query(log_type: &str, status_code: i32, duration_ms: i32) {
+    get_metrics().db_query.with_label_values(&[log_type, &status_code.to_string()]).observe(secs(duration_ms));
+}
+
+pub(crate) fn dist_lock_wait(backend: &str, status_code: i32, duration_ms: i32) {
+    get_metrics().dist_lock_wait.with_label_values(&[backend, &status_code.to_string()]).observe(secs(duration_ms));
+}
+
+pub(crate) fn job_run(name: &str, status_code: i32, duration_ms: i32) {
+    let m = get_metrics();
+    m.job_run.with_label_values(&[name, &status_code.to_string()]).observe(secs(duration_ms));
+    if status_code >= 400 {
+        m.job_failures.with_label_values(&[name]).inc();
+    }
+}
+
+pub(crate) fn cache_lookup(group: &str, is_hit: bool) {
+    get_metrics().cache_lookup.with_label_values(&[group, if is_hit { "hit" } else { "miss" }]).inc();
+}
+
+pub(crate) fn clog_dropped(log_type: &str) {
+    get_metrics().clog_dropped.with_label_values(&[log_type]).inc();
+}
@@ -0,0 +1,28 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::*;
+
+#[test]
+fn test_render() {
+    fuse_request("GET: /test/metrics", 200, 1500);
+    http_client("example.com", 503, 20);
+    job_run("metrics-test-job", 500, 10);
+    cache_lookup("metrics-test-group", true);
+    cache_lookup("metrics-test-group", false);
+    cache_lookup("metrics-test-group", false);
+
+    let out = render();
+    assert!(out.contains(r#"rmod_fuse_request_duration_seconds_sum{endpoint="GET: /test/metrics",status="200"} 1.5"#), "{}", out);
+    assert!(out.contains(r#"rmod_http_client_duration_seconds_count{domain="example.com",status="503"} 1"#), "{}", out);
+    assert!(out.contains(r#"rmod_job_failures_total{job="metrics-test-job"} 1"#), "{}", out);
+    assert!(out.contains(r#"rmod_cache_lookups_total{group="metrics-test-group",result="hit"} 1"#), "{}", out);
+    assert!(out.contains(r#"rmod_cache_lookups_total{group="metrics-test-group",result="miss"} 2"#), "{}", out);
+}
@@ -176,3 +176,24 @@ pub(crate) fn db_is_read_real() -> bool {
 pub(crate) fn db_is_read_real_on(key: &str) -> bool {
     get_pools(key).read.is_some()
 }
+
+/// Pings every registered pool (write and read) with `SELECT 1`, keyed as `<key>` and `<key>:read`.
+pub(crate) async fn db_ping_all() -> Vec<(String, Result<(), String>)> {
+    let pools: Vec<(String, &'static DbPools)> = {
+        let store = get_db_store().read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        store.keys.iter().filter_map(|k| store.map.get(k).map(|p| (k.clone(), *p))).collect()
+    };
+
+    let mut results = Vec::new();
+    for (key, pools) in pools {
+        results.push((key.clone(), ping(&pools.write).await));
+        if let Some(read) = &pools.read {
+            results.push((format!("{}:read", key), ping(read).await));
+        }
+    }
+    results
+}
+
+async fn ping(pool: &Pool<Postgres>) -> Result<(), String> {
+    sqlx::query("SELECT 1").execute(pool).await.map(|_| ()).map_err(|e| e.to_string())
+}
@@ -15,14 +15,17 @@ mod tests;
 use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use chrono::{TimeDelta, Utc};
 use hmac::{Hmac, Mac};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
 use sha2::Sha256;
 use std::sync::OnceLock;
 
+use crate::config::JwtConfig;
+
 type HmacSha256 = Hmac<Sha256>;
 static ENCODED_HEADER: OnceLock<String> = OnceLock::new();
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,
     pub iss: String,
@@ -36,14 +39,18 @@ pub fn encode(sub: String, iss: String, secret: &str, duration: TimeDelta) -> St
     let exp = (timenow + duration).timestamp() as u32;
 
     let claims = Claims { sub, iss, iat, exp };
+    encode_claims(&claims, secret)
+}
 
+/// Signs any claims, e.g. a struct carrying `aud` or `nbf` next to the `Claims` fields.
+pub fn encode_claims<T: Serialize>(claims: &T, secret: &str) -> String {
     let encoded_header = ENCODED_HEADER.get_or_init(|| {
         let header = serde_json::json!({"alg": "HS256", "typ": "JWT"});
         let header_json = serde_json::to_string(&header).unwrap();
         URL_SAFE_NO_PAD.encode(header_json.as_bytes())
     });
 
-    let payload_json = serde_json::to_string(&claims).unwrap();
+    let payload_json = serde_json::to_string(claims).unwrap();
     let encoded_payload = URL_SAFE_NO_PAD.encode(payload_json.as_bytes());
 
     let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
@@ -86,6 +93,95 @@ pub fn decode(token: &str, secret: &str) -> Result<Claims, String> {
     Ok(payload)
 }
 
+/// Payload of a token accepted by `verify`, with every registered and custom claim.
+#[derive(Clone, Debug)]
+pub struct VerifiedClaims(pub Value);
+
+impl VerifiedClaims {
+    pub fn sub(&self) -> Option<&str> {
+        self.0["sub"].as_str()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.0.get(name)
+    }
+
+    /// Typed view of the payload, e.g. `Claims` or a struct with the custom claims.
+    pub fn parse<T: DeserializeOwned>(&self) -> Result<T, String> {
+        T::deserialize(&self.0).map_err(|e| format!("failed to parse claims: {}", e))
+    }
+}
+
+/// Decodes a token signed with any of `config.secrets`, checking `exp`, and when present `nbf` and `iat`, with
+/// `config.leeway` and, when configured, the `iss` and `aud` claims.
+pub fn verify(token: &str, config: &JwtConfig) -> Result<VerifiedClaims, String> {
+    let mut parts = token.split('.');
+    let header_part = parts.next().ok_or("invalid token format")?;
+    let payload_part = parts.next().ok_or("invalid token format")?;
+    let signature_part = parts.next().ok_or("invalid token format")?;
+    if parts.next().is_some() {
+        return Err("invalid token format".to_string());
+    }
+
+    let header_bytes = URL_SAFE_NO_PAD.decode(header_part).map_err(|e| format!("invalid header encoding: {}", e))?;
+    let header: Value = serde_json::from_slice(&header_bytes).map_err(|e| format!("failed to parse header: {}", e))?;
+    if header["alg"] != "HS256" {
+        return Err("unsupported algorithm".to_string());
+    }
+
+    let data = &token[..header_part.len() + 1 + payload_part.len()];
+    let signature = URL_SAFE_NO_PAD.decode(signature_part).map_err(|e| format!("invalid signature encoding: {}", e))?;
+    let signed = config.secrets.iter().any(|secret| {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+        mac.update(data.as_bytes());
+        mac.verify_slice(&signature).is_ok()
+    });
+    if !signed {
+        return Err("invalid signature".to_string());
+    }
+
+    let payload_bytes = URL_SAFE_NO_PAD.decode(payload_part).map_err(|e| format!("invalid payload encoding: {}", e))?;
+    let payload: Value = serde_json::from_slice(&payload_bytes).map_err(|e| format!("failed to parse payload: {}", e))?;
+    if !payload.is_object() {
+        return Err("failed to parse payload: not an object".to_string());
+    }
+
+    let now = Utc::now().timestamp();
+    let leeway = config.leeway.unwrap_or(60);
+    let exp = payload["exp"].as_i64().ok_or("missing exp claim")?;
+    if exp + leeway < now {
+        return Err("token expired".to_string());
+    }
+    if let Some(nbf) = payload["nbf"].as_i64()
+        && nbf - leeway > now
+    {
+        return Err("token not yet valid".to_string());
+    }
+    if let Some(iat) = payload["iat"].as_i64()
+        && iat - leeway > now
+    {
+        return Err("token issued in the future".to_string());
+    }
+
+    if let Some(issuer) = &config.issuer
+        && payload["iss"] != issuer.as_str()
+    {
+        return Err("invalid issuer".to_string());
+    }
+    if let Some(audience) = &config.audience {
+        let matched = match &payload["aud"] {
+            Value::String(aud) => aud == audience,
+            Value::Array(auds) => auds.iter().any(|aud| aud == audience.as_str()),
+            _ => false,
+        };
+        if !matched {
+            return Err("invalid audience".to_string());
+        }
+    }
+
+    Ok(VerifiedClaims(payload))
+}
+
 pub fn unsafe_decode(token: &str) -> Option<Claims> {
     let mut parts = token.split('.');
     let _header = parts.next()?;
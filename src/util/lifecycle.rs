@@ -10,7 +10,8 @@
 
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::{LazyLock, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex, OnceLock};
 use std::time::Duration;
 use tokio::signal::unix::{SignalKind, signal};
 use tokio::sync::broadcast;
@@ -23,11 +24,46 @@ static CALLBACKS: LazyLock<Mutex<Vec<ShutdownCallback>>> = LazyLock::new(|| Mute
 static STARTED: LazyLock<Mutex<bool>> = LazyLock::new(|| Mutex::new(false));
 static SHUTDOWN_TX: LazyLock<broadcast::Sender<()>> = LazyLock::new(|| broadcast::channel(1).0);
 static WAIT_TX: LazyLock<broadcast::Sender<()>> = LazyLock::new(|| broadcast::channel(1).0);
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+static READINESS_SERVED: AtomicBool = AtomicBool::new(false);
+static READY_DRAIN: OnceLock<Duration> = OnceLock::new();
 
 pub fn subscribe() -> broadcast::Receiver<()> {
     SHUTDOWN_TX.subscribe()
 }
 
+/// True once SIGINT/SIGTERM has been received, while the graceful wait is still running.
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::Relaxed)
+}
+
+/// First step of the shutdown: `/readyz` turns not-ready while the servers keep accepting for `duration`,
+/// so load balancers stop routing here before the listeners close.
+pub(crate) async fn drain(duration: Duration) {
+    SHUTTING_DOWN.store(true, Ordering::Relaxed);
+    tokio::time::sleep(duration).await;
+}
+
+/// Called by fuse when it serves `/readyz`, which turns the default drain on.
+pub(crate) fn serve_readiness() {
+    READINESS_SERVED.store(true, Ordering::Relaxed);
+}
+
+/// How long `/readyz` reports not-ready before the listeners close, from `RMOD_READY_DRAIN` (e.g. `5s`). Without
+/// it the drain is `5s` when fuse serves `/readyz` and zero otherwise, so services without it stop right away.
+fn ready_drain() -> Duration {
+    *READY_DRAIN.get_or_init(|| match crate::util::env::string_opt("RMOD_READY_DRAIN") {
+        Some(v) => crate::time::to_duration(&v),
+        None if READINESS_SERVED.load(Ordering::Relaxed) => Duration::from_secs(5),
+        None => Duration::ZERO,
+    })
+}
+
+#[cfg(test)]
+pub(crate) fn reset_shutting_down() {
+    SHUTTING_DOWN.store(false, Ordering::Relaxed);
+}
+
 pub async fn wait() {
     let mut rx = WAIT_TX.subscribe();
     let _ = rx.recv().await;
@@ -66,13 +102,15 @@ pub fn start() {
         };
 
         let start_time = tokio::time::Instant::now();
-        let _ = SHUTDOWN_TX.send(());
-
         let wait_duration = {
             let guard = SHUTDOWN_DURATION.lock().unwrap();
             (*guard).unwrap_or(Duration::from_secs(10))
         };
 
+        // never longer than the graceful wait
+        drain(ready_drain().min(wait_duration)).await;
+        let _ = SHUTDOWN_TX.send(());
+
         let cbs = {
             let mut guard = CALLBACKS.lock().unwrap();
             std::mem::take(&mut *guard)
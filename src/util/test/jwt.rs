@@ -55,3 +55,73 @@ fn test_jwt_expired() {
     assert!(result.is_err());
     assert_eq!(result.err().unwrap(), "token expired");
 }
+
+fn verify_config() -> crate::config::JwtConfig {
+    crate::config::JwtConfig {
+        secrets: vec!["new".to_string(), "old".to_string()],
+        issuer: Some("rmod".to_string()),
+        audience: Some("api".to_string()),
+        leeway: Some(30),
+    }
+}
+
+#[test]
+fn test_jwt_verify() {
+    let config = verify_config();
+    let now = chrono::Utc::now().timestamp();
+
+    let token = encode_claims(&serde_json::json!({"sub": "u1", "iss": "rmod", "aud": "api", "iat": now, "exp": now + 60}), "old");
+    assert_eq!(verify(&token, &config).unwrap().sub(), Some("u1"));
+
+    let token = encode_claims(&serde_json::json!({"sub": "u1", "iss": "rmod", "aud": ["web", "api"], "iat": now, "exp": now - 10}), "new");
+    assert!(verify(&token, &config).is_ok(), "expired within leeway");
+
+    let cases = [
+        (serde_json::json!({"sub": "u1", "iss": "rmod", "aud": "api", "iat": now, "exp": now - 60}), "new", "token expired"),
+        (
+            serde_json::json!({"sub": "u1", "iss": "rmod", "aud": "api", "iat": now, "exp": now + 60, "nbf": now + 60}),
+            "new",
+            "token not yet valid",
+        ),
+        (serde_json::json!({"sub": "u1", "iss": "other", "aud": "api", "iat": now, "exp": now + 60}), "new", "invalid issuer"),
+        (serde_json::json!({"sub": "u1", "iss": "rmod", "aud": ["web"], "iat": now, "exp": now + 60}), "new", "invalid audience"),
+        (serde_json::json!({"sub": "u1", "iss": "rmod", "iat": now, "exp": now + 60}), "new", "invalid audience"),
+        (serde_json::json!({"sub": "u1", "iss": "rmod", "aud": "api", "iat": now, "exp": now + 60}), "retired", "invalid signature"),
+    ];
+    for (claims, secret, err) in cases {
+        assert_eq!(verify(&encode_claims(&claims, secret), &config).unwrap_err(), err);
+    }
+}
+
+#[test]
+fn test_jwt_verify_rejects_other_algorithms() {
+    let token = encode("u1".to_string(), "rmod".to_string(), "new", time::to_delta("1m"));
+    let (_, rest) = token.split_once('.').unwrap();
+    let header = URL_SAFE_NO_PAD.encode(br#"{"alg":"none","typ":"JWT"}"#);
+    let mut config = verify_config();
+    config.audience = None;
+
+    assert!(verify(&token, &config).is_ok());
+    assert_eq!(verify(&format!("{}.{}", header, rest), &config).unwrap_err(), "unsupported algorithm");
+}
+
+#[test]
+fn test_jwt_verify_keeps_all_claims() {
+    #[derive(serde::Deserialize)]
+    struct PartnerClaims {
+        sub: String,
+        scope: Vec<String>,
+    }
+
+    // no `iss` nor `iat`, which only matter when configured or present
+    let config = crate::config::JwtConfig { secrets: vec!["new".to_string()], issuer: None, audience: None, leeway: None };
+    let now = chrono::Utc::now().timestamp();
+    let token = encode_claims(&serde_json::json!({"sub": "p1", "exp": now + 60, "aud": "api", "scope": ["orders"]}), "new");
+
+    let claims = verify(&token, &config).unwrap();
+    assert_eq!(claims.sub(), Some("p1"));
+    assert_eq!(claims.get("aud").unwrap(), "api");
+    let partner: PartnerClaims = claims.parse().unwrap();
+    assert_eq!((partner.sub.as_str(), partner.scope), ("p1", vec!["orders".to_string()]));
+    assert!(claims.parse::<Claims>().is_err());
+}
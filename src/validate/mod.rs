@@ -0,0 +1,21 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+#[cfg(test)]
+#[path = "test/validate.rs"]
+mod tests;
+
+mod rules;
+mod validator;
+
+pub use regex::Regex;
+pub use rmod_macros::Validate;
+pub use rules::*;
+pub use validator::*;
@@ -0,0 +1,254 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+//! Rules used by `#[derive(Validate)]`. A `None` field passes every rule except `required`.
+
+use super::Validator;
+use crate::fct::FCT;
+use regex::Regex;
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap};
+
+pub trait HasLength {
+    /// Characters for text, items for collections.
+    fn validate_len(&self) -> Option<usize>;
+}
+
+pub trait AsNumber {
+    fn validate_num(&self) -> Option<Decimal>;
+}
+
+pub trait AsText {
+    fn validate_text(&self) -> Option<&str>;
+}
+
+pub trait HasScale {
+    fn validate_scale(&self) -> Option<u32>;
+}
+
+pub trait Presence {
+    fn is_present(&self) -> bool;
+}
+
+pub fn required<T: Presence + ?Sized>(v: &mut Validator, val: &T) {
+    if !val.is_present() {
+        v.error("required", "is required");
+    }
+}
+
+pub fn length<T: HasLength + ?Sized>(v: &mut Validator, val: &T, min: Option<usize>, max: Option<usize>) {
+    let Some(len) = val.validate_len() else {
+        return;
+    };
+    if min.is_some_and(|m| len < m) || max.is_some_and(|m| len > m) {
+        v.error("length", bounds_message("length", min, max));
+    }
+}
+
+/// `min` and `max` are decimal literals, checked when the derive expands.
+pub fn range<T: AsNumber + ?Sized>(v: &mut Validator, val: &T, min: Option<&str>, max: Option<&str>) {
+    let Some(num) = val.validate_num() else {
+        return;
+    };
+    let min_val = min.and_then(|m| m.parse::<Decimal>().ok());
+    let max_val = max.and_then(|m| m.parse::<Decimal>().ok());
+    if min_val.is_some_and(|m| num < m) || max_val.is_some_and(|m| num > m) {
+        v.error("range", bounds_message("value", min, max));
+    }
+}
+
+pub fn precision<T: HasScale + ?Sized>(v: &mut Validator, val: &T, max_scale: u32) {
+    if val.validate_scale().is_some_and(|s| s > max_scale) {
+        v.error("precision", format!("must have at most {} decimal places", max_scale));
+    }
+}
+
+pub fn regex<T: AsText + ?Sized>(v: &mut Validator, val: &T, re: &Regex) {
+    if val.validate_text().is_some_and(|s| !re.is_match(s)) {
+        v.error("regex", "has an invalid format");
+    }
+}
+
+pub fn one_of<T: AsText + ?Sized>(v: &mut Validator, val: &T, options: &[&str]) {
+    if val.validate_text().is_some_and(|s| !options.contains(&s)) {
+        v.error("one_of", format!("must be one of: {}", options.join(", ")));
+    }
+}
+
+fn bounds_message(name: &str, min: Option<impl std::fmt::Display>, max: Option<impl std::fmt::Display>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) => format!("{} must be between {} and {}", name, min, max),
+        (Some(min), None) => format!("{} must be at least {}", name, min),
+        (None, Some(max)) => format!("{} must be at most {}", name, max),
+        (None, None) => format!("{} is out of range", name),
+    }
+}
+
+impl<T: ?Sized + HasLength> HasLength for &T {
+    fn validate_len(&self) -> Option<usize> {
+        (**self).validate_len()
+    }
+}
+
+impl<T: HasLength> HasLength for Option<T> {
+    fn validate_len(&self) -> Option<usize> {
+        self.as_ref()?.validate_len()
+    }
+}
+
+impl HasLength for str {
+    fn validate_len(&self) -> Option<usize> {
+        Some(self.chars().count())
+    }
+}
+
+impl HasLength for String {
+    fn validate_len(&self) -> Option<usize> {
+        Some(self.chars().count())
+    }
+}
+
+impl<T> HasLength for Vec<T> {
+    fn validate_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T> HasLength for [T] {
+    fn validate_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<K, T> HasLength for HashMap<K, T> {
+    fn validate_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<K, T> HasLength for BTreeMap<K, T> {
+    fn validate_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T: ?Sized + AsNumber> AsNumber for &T {
+    fn validate_num(&self) -> Option<Decimal> {
+        (**self).validate_num()
+    }
+}
+
+impl<T: AsNumber> AsNumber for Option<T> {
+    fn validate_num(&self) -> Option<Decimal> {
+        self.as_ref()?.validate_num()
+    }
+}
+
+macro_rules! as_number_int {
+    ($($t:ty),*) => {
+        $(impl AsNumber for $t {
+            fn validate_num(&self) -> Option<Decimal> {
+                Some(Decimal::from(*self))
+            }
+        })*
+    };
+}
+
+as_number_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl AsNumber for f32 {
+    fn validate_num(&self) -> Option<Decimal> {
+        Decimal::try_from(*self).ok()
+    }
+}
+
+impl AsNumber for f64 {
+    fn validate_num(&self) -> Option<Decimal> {
+        Decimal::try_from(*self).ok()
+    }
+}
+
+impl AsNumber for Decimal {
+    fn validate_num(&self) -> Option<Decimal> {
+        Some(*self)
+    }
+}
+
+impl AsNumber for FCT {
+    fn validate_num(&self) -> Option<Decimal> {
+        Some(self.0)
+    }
+}
+
+impl<T: ?Sized + AsText> AsText for &T {
+    fn validate_text(&self) -> Option<&str> {
+        (**self).validate_text()
+    }
+}
+
+impl<T: AsText> AsText for Option<T> {
+    fn validate_text(&self) -> Option<&str> {
+        self.as_ref()?.validate_text()
+    }
+}
+
+impl AsText for str {
+    fn validate_text(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl AsText for String {
+    fn validate_text(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl<T: ?Sized + HasScale> HasScale for &T {
+    fn validate_scale(&self) -> Option<u32> {
+        (**self).validate_scale()
+    }
+}
+
+impl<T: HasScale> HasScale for Option<T> {
+    fn validate_scale(&self) -> Option<u32> {
+        self.as_ref()?.validate_scale()
+    }
+}
+
+impl HasScale for Decimal {
+    fn validate_scale(&self) -> Option<u32> {
+        Some(self.normalize().scale())
+    }
+}
+
+impl HasScale for FCT {
+    fn validate_scale(&self) -> Option<u32> {
+        Some(self.0.normalize().scale())
+    }
+}
+
+impl<T> Presence for Option<T> {
+    fn is_present(&self) -> bool {
+        self.is_some()
+    }
+}
+
+impl Presence for String {
+    fn is_present(&self) -> bool {
+        !self.trim().is_empty()
+    }
+}
+
+impl<T> Presence for Vec<T> {
+    fn is_present(&self) -> bool {
+        !self.is_empty()
+    }
+}
@@ -0,0 +1,78 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use super::*;
+use crate::FCT;
+use crate::fct;
+
+fn trading_code(code: &str) -> Result<(), String> {
+    if code.starts_with("X-") { Ok(()) } else { Err("must start with X-".to_string()) }
+}
+
+#[derive(Validate, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Order {
+    #[validate(length(min = 3, max = 8), regex = "^[a-z]+$")]
+    user_name: String,
+    #[validate(one_of("buy", "sell"))]
+    side: String,
+    #[validate(range(min = 0, max = "100.5"), precision = 2)]
+    unit_price: FCT,
+    #[validate(required, range(min = -5))]
+    offset: Option<i32>,
+    #[validate(length(min = 1), nested)]
+    items: Vec<Item>,
+    #[serde(rename = "ref")]
+    #[validate(custom = "trading_code")]
+    reference: String,
+}
+
+#[derive(Validate, serde::Deserialize)]
+struct Item {
+    #[validate(range(min = 1))]
+    qty: i64,
+}
+
+#[test]
+fn test_validate() {
+    let order = Order {
+        user_name: "andy".to_string(),
+        side: "buy".to_string(),
+        unit_price: fct!("100.50"),
+        offset: Some(-5),
+        items: vec![Item { qty: 1 }],
+        reference: "X-1".to_string(),
+    };
+    assert_eq!(order.validate(), Ok(()));
+
+    let order = Order {
+        user_name: "An".to_string(),
+        side: "hold".to_string(),
+        unit_price: fct!("100.505"),
+        offset: None,
+        items: vec![Item { qty: 1 }, Item { qty: 0 }],
+        reference: "1".to_string(),
+    };
+    let errors: Vec<(String, &str, String)> = order.validate().unwrap_err().into_iter().map(|e| (e.path, e.code, e.message)).collect();
+    let errors: Vec<(&str, &str, &str)> = errors.iter().map(|(p, c, m)| (p.as_str(), *c, m.as_str())).collect();
+    assert_eq!(
+        errors,
+        vec![
+            ("userName", "length", "length must be between 3 and 8"),
+            ("userName", "regex", "has an invalid format"),
+            ("side", "one_of", "must be one of: buy, sell"),
+            ("unitPrice", "range", "value must be between 0 and 100.5"),
+            ("unitPrice", "precision", "must have at most 2 decimal places"),
+            ("offset", "required", "is required"),
+            ("items[1].qty", "range", "value must be at least 1"),
+            ("ref", "custom", "must start with X-"),
+        ]
+    );
+}
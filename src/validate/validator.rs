@@ -0,0 +1,133 @@
+/*
+ * Copyright (c) 2026.
+ * Created by Andy Pangaribuan (iam.pangaribuan@gmail.com)
+ * https://github.com/apangaribuan
+ *
+ * This product is protected by copyright and distributed under
+ * licenses restricting copying, distribution and decompilation.
+ * All Rights Reserved.
+ */
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Field checks of a request type, usually written with `#[derive(Validate)]` and `#[validate(...)]`
+/// on the fields; see `FuseRContext::json_valid`.
+pub trait Validate {
+    fn check(&self, v: &mut Validator);
+
+    /// Runs every check and returns all failures, not only the first.
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::default();
+        self.check(&mut v);
+        if v.errors.is_empty() { Ok(()) } else { Err(v.errors) }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    /// JSON path of the field in the `serde_path_to_error` format, e.g. `items[0].price`.
+    pub path: String,
+    pub code: &'static str,
+    pub message: String,
+}
+
+enum Segment {
+    Field(&'static str),
+    Key(String),
+    Index(usize),
+}
+
+/// Collects field errors while walking a value; keeps track of the current JSON path.
+#[derive(Default)]
+pub struct Validator {
+    path: Vec<Segment>,
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub fn field(&mut self, name: &'static str, f: impl FnOnce(&mut Self)) {
+        self.path.push(Segment::Field(name));
+        f(self);
+        self.path.pop();
+    }
+
+    pub fn index(&mut self, index: usize, f: impl FnOnce(&mut Self)) {
+        self.path.push(Segment::Index(index));
+        f(self);
+        self.path.pop();
+    }
+
+    pub fn key(&mut self, key: impl ToString, f: impl FnOnce(&mut Self)) {
+        self.path.push(Segment::Key(key.to_string()));
+        f(self);
+        self.path.pop();
+    }
+
+    /// Records an error on the current path.
+    pub fn error(&mut self, code: &'static str, message: impl Into<String>) {
+        let path = self.path();
+        self.errors.push(FieldError { path, code, message: message.into() });
+    }
+
+    pub fn path(&self) -> String {
+        if self.path.is_empty() {
+            return ".".to_string();
+        }
+
+        let mut path = String::new();
+        for seg in &self.path {
+            match seg {
+                Segment::Field(name) => push_name(&mut path, name),
+                Segment::Key(key) => push_name(&mut path, key),
+                Segment::Index(i) => path.push_str(&format!("[{}]", i)),
+            }
+        }
+        path
+    }
+}
+
+fn push_name(path: &mut String, name: &str) {
+    if !path.is_empty() {
+        path.push('.');
+    }
+    path.push_str(name);
+}
+
+impl<T: Validate> Validate for Option<T> {
+    fn check(&self, v: &mut Validator) {
+        if let Some(val) = self {
+            val.check(v);
+        }
+    }
+}
+
+impl<T: Validate> Validate for Box<T> {
+    fn check(&self, v: &mut Validator) {
+        self.as_ref().check(v);
+    }
+}
+
+impl<T: Validate> Validate for Vec<T> {
+    fn check(&self, v: &mut Validator) {
+        for (i, val) in self.iter().enumerate() {
+            v.index(i, |v| val.check(v));
+        }
+    }
+}
+
+impl<K: ToString, T: Validate> Validate for HashMap<K, T> {
+    fn check(&self, v: &mut Validator) {
+        for (key, val) in self {
+            v.key(key.to_string(), |v| val.check(v));
+        }
+    }
+}
+
+impl<K: ToString, T: Validate> Validate for BTreeMap<K, T> {
+    fn check(&self, v: &mut Validator) {
+        for (key, val) in self {
+            v.key(key.to_string(), |v| val.check(v));
+        }
+    }
+}